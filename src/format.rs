@@ -0,0 +1,153 @@
+const BYTE_UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < BYTE_UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, BYTE_UNITS[unit])
+}
+
+pub fn format_duration(seconds: u64) -> String {
+    let days = seconds / 86400;
+    let hours = (seconds % 86400) / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+
+    if days > 0 {
+        format!("{}d {}h {}m {}s", days, hours, minutes, secs)
+    } else if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, secs)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, secs)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+pub fn format_micros(micros: u64) -> String {
+    if micros < 1000 {
+        format!("{} µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.2} ms", micros as f64 / 1000.0)
+    } else {
+        format!("{:.2} s", micros as f64 / 1_000_000.0)
+    }
+}
+
+/// Formats a unix timestamp as a UTC date and time.
+pub fn format_timestamp(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86400);
+    let seconds = timestamp.rem_euclid(86400);
+
+    // Civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+/// Formats a fractional unix timestamp as a UTC date and time with
+/// millisecond precision.
+pub fn format_timestamp_millis(timestamp: f64) -> String {
+    format!(
+        "{}.{:03}",
+        format_timestamp(timestamp as i64),
+        (timestamp.fract() * 1000.0) as u32
+    )
+}
+
+/// Fields holding a number of seconds, like `uptime_in_seconds` or
+/// `rdb_last_bgsave_time_sec`. Rates such as `instantaneous_ops_per_sec` end in
+/// `_sec` as well but stay plain numbers.
+fn is_duration_field(field: &str) -> bool {
+    field.ends_with("_seconds") || field.ends_with("_seconds_ago") || field.ends_with("_time_sec")
+}
+
+/// Makes a raw INFO field readable, turning byte counts and second counts into
+/// human units. Values that don't look like either are returned untouched.
+pub fn format_info_value(field: &str, value: &str) -> String {
+    if field.starts_with("db") && value.contains('=') {
+        return value
+            .split(',')
+            .map(|part| match part.split_once('=') {
+                Some(("avg_ttl", ms)) => match ms.parse::<u64>() {
+                    Ok(ms) => format!("avg_ttl={}", format_duration(ms / 1000)),
+                    Err(_) => part.to_string(),
+                },
+                _ => part.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+    }
+
+    let number = match value.parse::<u64>() {
+        Ok(n) => n,
+        Err(_) => return value.to_string(),
+    };
+
+    if (field.contains("memory") && !field.contains("ratio")) || field.ends_with("_bytes") {
+        format_bytes(number)
+    } else if is_duration_field(field) {
+        format_duration(number)
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_switch_units_at_1024() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.00 KB");
+        assert_eq!(format_bytes(1536), "1.50 KB");
+        assert_eq!(format_bytes(1024 * 1024), "1.00 MB");
+        assert_eq!(format_bytes(1024u64.pow(5)), "1024.00 TB");
+    }
+
+    #[test]
+    fn durations_drop_leading_zero_units() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(59), "59s");
+        assert_eq!(format_duration(60), "1m 0s");
+        assert_eq!(format_duration(3600), "1h 0m 0s");
+        assert_eq!(format_duration(90061), "1d 1h 1m 1s");
+    }
+
+    #[test]
+    fn timestamps_are_utc_dates() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(-1), "1969-12-31 23:59:59");
+        assert_eq!(format_timestamp(1709210096), "2024-02-29 12:34:56");
+        assert_eq!(format_timestamp(951782400), "2000-02-29 00:00:00");
+        // 2100 is not a leap year, so February ends on the 28th
+        assert_eq!(format_timestamp(4107542400 - 86400), "2100-02-28 00:00:00");
+        assert_eq!(format_timestamp_millis(1.25), "1970-01-01 00:00:01.250");
+    }
+}
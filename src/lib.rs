@@ -1,306 +1,5466 @@
 #![feature(default_free_fn)]
 
 extern crate redis;
+mod format;
+mod memory;
+mod metrics;
+mod rdb;
 mod redislogic;
+mod script;
+mod snapshot;
+mod streams;
 mod style;
+mod treemap;
 
-use std::{collections::HashMap, default::default};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    default::default,
+    fmt, fs,
+    path::Path,
+    time::{Duration, Instant},
+};
 
 use crate::redislogic::redislogic::get_redis_value;
+use format::{
+    format_bytes, format_duration, format_info_value, format_micros, format_timestamp,
+    format_timestamp_millis,
+};
 use generational_arena::{Arena, Index};
 use iced::{
-    button, executor, scrollable, text_input, Align, Application, Button, Clipboard, Column,
-    Command, Container, Element, Length, Row, Scrollable, Text, TextInput,
+    button, executor, keyboard, mouse, pick_list, scrollable, text_input, time, Align, Application,
+    Button, Canvas, Checkbox, Clipboard, Column, Command, Container, Element, Length, PickList,
+    Point, Rectangle, Row, Scrollable, Size, Space, Subscription, Text, TextInput,
 };
+use memory::{
+    aggregate_namespaces, build_memory_tree, memory_tree_children, top_keys, MemoryTreeNode,
+    NamespaceMemory,
+};
+use metrics::{ChartWindow, Metric, MetricChart, MetricSample, MetricsHistory};
+use rdb::{RdbDatabase, RdbFile};
 use redislogic::redislogic::{
-    connect_redis, convert_keys_to_namespaces, delete_redis_key, get_all_keys, set_redis_value,
-    RedisNamespace, RedisValue,
+    connect_redis, convert_keys_to_namespaces, copy_keys, delete_redis_key,
+    enable_keyevent_notifications, get_client_id, get_client_list, get_config_value,
+    get_key_metadata, get_keyspace, get_pubsub_channels, get_server_info, get_slowlog, glob_match,
+    kill_client, namespace_patterns, natural_cmp, ping, publish_message, reset_slowlog, scan_keys,
+    select_db, set_config_value, set_redis_value, split_key, test_connection, unlink_keys,
+    ClientInfo, ConnectionSettings, InfoSection, KeyMemory, KeyMetadata, KeyspaceDb, MonitorEntry,
+    RedisNamespace, RedisValue, SlowlogEntry, DEFAULT_NAMESPACE_DELIMITERS,
+};
+use script::{
+    parse_commands, run_commands, write_script, CommandsReport, FileFormat, RedisCommand,
 };
+use snapshot::{
+    dump_keys, import_keys, preview_import, ConflictPolicy, ImportPreview, ImportReport,
+    KeysSnapshot,
+};
+use streams::{
+    KeyScanEvent, KeyScanStream, KeyspaceEvent, KeyspaceStream, MemoryScanEvent, MemoryScanStream,
+    MonitorEvent, MonitorStream, PatternDeleteEvent, PatternDeleteProgress, PatternDeleteStream,
+    PubSubEvent, PubSubMessage, PubSubStream,
+};
+use treemap::MemoryTreemap;
+
+const INFO_SECTIONS: [&str; 7] = [
+    "server",
+    "clients",
+    "memory",
+    "persistence",
+    "stats",
+    "replication",
+    "keyspace",
+];
+
+const DEFAULT_SLOWLOG_COUNT: usize = 128;
+const SLOWLOG_THRESHOLD_CONFIG: &str = "slowlog-log-slower-than";
+const MAX_MONITOR_ENTRIES: usize = 1000;
+const MAX_PUBSUB_MESSAGES: usize = 1000;
+/// The wait before subscribing again after a Pub/Sub connection failed.
+const PUBSUB_RETRY_DELAY: Duration = Duration::from_secs(5);
+const NOTIFY_KEYSPACE_EVENTS_CONFIG: &str = "notify-keyspace-events";
+const KEY_TREE_WINDOW: usize = 100;
+/// Every row of the key list has this height, so the scroll offset tells
+/// which rows are visible.
+const KEY_ROW_HEIGHT: u16 = 30;
+const KEY_PAGE_SIZE: usize = 500;
+const DELETE_PREVIEW_KEYS: usize = 100;
+const IMPORT_REPORT_FAILURES: usize = 10;
+const COPY_REPORT_HEIGHT: u16 = 120;
+/// What MEMORY USAGE samples of a collection unless told otherwise.
+const MEMORY_USAGE_SAMPLES: usize = 5;
+const DEFAULT_TOP_KEYS: usize = 50;
+const MAX_MEMORY_NAMESPACE_ROWS: usize = 500;
+/// How often a connected tab PINGs its server.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// The wait before the first reconnect attempt, doubled after every failure.
+const MIN_RECONNECT_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(60);
+const DEFAULT_CONNECT_TIMEOUT_MILLIS: &str = "5000";
+const DEFAULT_IO_TIMEOUT_MILLIS: &str = "30000";
 
 pub struct RedisViewer {
     server_tabs: Arena<ServerTab>,
     current_server_tab_index: Option<Index>,
     conn_form_state: ConnectionFormState,
     keys_refresh_button_state: button::State,
-    tab_buttons: Vec<(String, Index, button::State)>,
+    tab_buttons: Vec<TabButtons>,
+    tab_rename_state: TabRenameState,
     new_tab_button: button::State,
     create_key_button: button::State,
+    panel_buttons: Vec<(TabPanel, button::State)>,
+    modifiers: keyboard::Modifiers,
+    /// Whether the left mouse button is held, maybe dragging a scrollbar.
+    is_dragging: bool,
+}
+
+/// The buttons of one server tab, kept in the order the tabs are shown.
+struct TabButtons {
+    index: Index,
+    select: button::State,
+    close: button::State,
+    move_left: button::State,
+    move_right: button::State,
+    rename: button::State,
+}
+
+impl TabButtons {
+    fn new(index: Index) -> Self {
+        TabButtons {
+            index,
+            select: button::State::default(),
+            close: button::State::default(),
+            move_left: button::State::default(),
+            move_right: button::State::default(),
+            rename: button::State::default(),
+        }
+    }
+}
+
+/// The tab whose name is being edited in place of its button.
+struct TabRenameState {
+    renaming: Option<Index>,
+    name_value: String,
+    name_input_state: text_input::State,
 }
 
+impl TabRenameState {
+    fn new() -> Self {
+        TabRenameState {
+            renaming: None,
+            name_value: String::new(),
+            name_input_state: text_input::State::default(),
+        }
+    }
+}
+
+/// The key list only builds widgets for a window of `KEY_TREE_WINDOW` rows
+/// starting at `first_row`, reusing `row_buttons` for whatever is shown.
+/// Spaces above and below the window keep the scrollbar sized for all rows.
 #[derive(Default)]
 struct KeysScrollbarState {
     state: scrollable::State,
+    first_row: usize,
+    row_buttons: Vec<button::State>,
+    row_namespace_buttons: Vec<NamespaceRowButtons>,
+}
+
+impl KeysScrollbarState {
+    /// Moves the window to the first row the list is scrolled to.
+    fn follow_scroll(&mut self) {
+        self.first_row = scroll_offset(&self.state) as usize / KEY_ROW_HEIGHT as usize;
+    }
+
+    fn scroll_to_top(&mut self) {
+        self.state = scrollable::State::default();
+        self.first_row = 0;
+    }
+}
+
+/// The raw offset of a scrollable. iced only hands it out clamped to given
+/// bounds, so ask with bounds that clamp nothing.
+fn scroll_offset(state: &scrollable::State) -> u32 {
+    let bounds = Rectangle::new(Point::ORIGIN, Size::ZERO);
+    let content_bounds = Rectangle::new(Point::ORIGIN, Size::new(0.0, f32::MAX));
+    state.offset(bounds, content_bounds)
+}
+
+/// The bulk actions offered on a namespace row.
+#[derive(Default)]
+struct NamespaceRowButtons {
+    delete: button::State,
+    export: button::State,
+    copy: button::State,
 }
 
 struct ServerTab {
     name: String,
-    redis: redis::Connection,
-    keys: Vec<String>,
-    namespaces: HashMap<String, RedisNamespace>,
+    source: KeySource,
+    /// Every listed key, for membership checks. The order lives in
+    /// `namespaces_view`, which a flat list shows as its nameless root.
+    keys: HashSet<String>,
     namespaces_view: Vec<NamespaceView>,
     keys_scrollbar_state: KeysScrollbarState,
-    key_buttons: Vec<(String, button::State)>,
+    key_metadata: HashMap<String, KeyMetadata>,
+    key_selection_state: KeySelectionState,
     editor_state: EditorState,
+    key_grouping_state: KeyGroupingState,
+    keyspace_state: KeyspaceState,
+    health_state: HealthState,
+    /// The last command that failed outside a panel with a status of its own.
+    status: String,
+    /// Keys gathered by the running SCAN. They replace the tree once the scan
+    /// finishes, so the tree doesn't flicker through partial listings.
+    scanned_keys: Option<Vec<String>>,
+    key_scan_generation: u64,
+    live_keys_state: LiveKeysState,
+    pattern_delete_state: PatternDeleteState,
+    keys_export_state: KeysExportState,
+    keys_import_state: KeysImportState,
+    keys_copy_state: KeysCopyState,
+    panel: TabPanel,
+    info_state: InfoState,
+    metrics_state: MetricsState,
+    slowlog_state: SlowlogState,
+    clients_state: ClientsState,
+    monitor_state: MonitorState,
+    pubsub_state: PubSubState,
+    memory_state: MemoryState,
 }
 
-struct ConnectionFormState {
-    show_connection_form: bool,
-    conn_name_text_input_state: text_input::State,
-    conn_name_value: String,
-    conn_text_input_state: text_input::State,
-    conn_value: String,
-    port_text_input_state: text_input::State,
-    port_value: String,
-    db_text_input_state: text_input::State,
-    db_value: String,
-    connect_button: button::State,
+/// Where the keys of a tab come from. Tabs opened from an RDB file only
+/// browse the dump and never talk to a server.
+enum KeySource {
+    /// The settings reconnect the tab and open its background streams.
+    Server(redis::Connection, ConnectionSettings),
+    Rdb(RdbDatabase),
 }
 
-#[derive(Debug, Clone)]
-pub enum Message {
-    KeySelected(String),
-    SelectedValueChanged(String),
-    SelectedValueSaved,
-    SelectedValueDeleted,
-    ConnNameChanged(String),
-    ConnValueChanged(String),
-    PortValueChanged(String),
-    DbValueChanged(String),
-    ConnectRedis,
-    RefreshKeys,
-    ChangeTab(Index),
-    NewTab,
-    OpenCreateKeyForm,
-    CreateKey,
-    CreateKeyChanged(String),
-    CreateValueChanged(String),
-    NamespaceExpandToggle(Vec<usize>),
+impl KeySource {
+    /// The server connection, or `None` for a tab browsing a dump.
+    fn connection(&mut self) -> Option<&mut redis::Connection> {
+        match self {
+            KeySource::Server(con, _) => Some(con),
+            KeySource::Rdb(_) => None,
+        }
+    }
+
+    /// The settings the connection was opened with, or `None` for a dump.
+    fn settings(&self) -> Option<&ConnectionSettings> {
+        match self {
+            KeySource::Server(_, settings) => Some(settings),
+            KeySource::Rdb(_) => None,
+        }
+    }
+
+    /// Like `connection`, for callers that already report redis errors.
+    fn server_connection(&mut self) -> redis::RedisResult<&mut redis::Connection> {
+        self.connection().ok_or_else(|| {
+            redis::RedisError::from((
+                redis::ErrorKind::ClientError,
+                "a read-only dump has no server connection",
+            ))
+        })
+    }
 }
 
-#[derive(Debug, Clone)]
-enum EditorState {
-    Empty,
-    Edit(ValueEditState),
-    Create(KeyCreateState),
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectionStatus {
+    Connected,
+    /// The server stopped answering and no reconnect was tried yet.
+    Disconnected,
+    Reconnecting,
 }
 
-#[derive(Debug, Clone, Default)]
-struct ValueEditState {
-    key: String,
-    value: String,
-    value_input_state: text_input::State,
-    save_button_state: button::State,
-    delete_button_state: button::State,
+impl fmt::Display for ConnectionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ConnectionStatus::Connected => "Connected",
+            ConnectionStatus::Disconnected => "Disconnected",
+            ConnectionStatus::Reconnecting => "Reconnecting",
+        };
+        write!(f, "{}", name)
+    }
 }
 
-#[derive(Debug, Clone, Default)]
-struct KeyCreateState {
-    key: String,
-    key_input_state: text_input::State,
-    value: String,
-    value_input_state: text_input::State,
-    create_button_state: button::State,
+/// Whether the tab's connection still answers, and when to try again if not.
+struct HealthState {
+    status: ConnectionStatus,
+    error: String,
+    attempts: u32,
+    backoff: Duration,
+    retry_at: Option<Instant>,
 }
 
-struct NamespaceView {
-    namespace: String,
-    expand_button_state: button::State,
-    is_expanded: bool,
-    sub_namespaces: Vec<NamespaceView>,
-    key_buttons: Vec<(String, button::State)>,
+impl HealthState {
+    fn new() -> Self {
+        HealthState {
+            status: ConnectionStatus::Connected,
+            error: String::new(),
+            attempts: 0,
+            backoff: MIN_RECONNECT_BACKOFF,
+            retry_at: None,
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        self.status == ConnectionStatus::Connected
+    }
+
+    fn check_interval(&self) -> Duration {
+        if self.is_connected() {
+            HEALTH_CHECK_INTERVAL
+        } else {
+            Duration::from_secs(1)
+        }
+    }
+
+    fn connection_lost(&mut self, error: String) {
+        self.status = ConnectionStatus::Disconnected;
+        self.error = error;
+        self.attempts = 0;
+        self.backoff = MIN_RECONNECT_BACKOFF;
+        self.retry_at = Some(Instant::now() + self.backoff);
+    }
+
+    fn reconnect_failed(&mut self, error: String) {
+        self.status = ConnectionStatus::Reconnecting;
+        self.error = error;
+        self.attempts += 1;
+        self.backoff = (self.backoff * 2).min(MAX_RECONNECT_BACKOFF);
+        self.retry_at = Some(Instant::now() + self.backoff);
+    }
+
+    fn summary(&self) -> String {
+        match self.status {
+            ConnectionStatus::Connected => self.status.to_string(),
+            _ => {
+                let retry_in = self
+                    .retry_at
+                    .map(|retry_at| retry_at.saturating_duration_since(Instant::now()))
+                    .unwrap_or_default();
+                format!(
+                    "{} ({}), {} attempts, retrying in {}s",
+                    self.status,
+                    self.error,
+                    self.attempts,
+                    retry_in.as_secs()
+                )
+            }
+        }
+    }
 }
 
-impl NamespaceView {
-    fn new(redis_namespace: &RedisNamespace) -> Self {
-        let mut key_buttons = Vec::<(String, button::State)>::new();
-        for key in redis_namespace.keys.iter() {
-            key_buttons.push((key.clone(), button::State::default()));
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TabPanel {
+    Keys,
+    Info,
+    Metrics,
+    Slowlog,
+    Clients,
+    Monitor,
+    PubSub,
+    Memory,
+}
+
+impl TabPanel {
+    const ALL: [TabPanel; 8] = [
+        TabPanel::Keys,
+        TabPanel::Info,
+        TabPanel::Metrics,
+        TabPanel::Slowlog,
+        TabPanel::Clients,
+        TabPanel::Monitor,
+        TabPanel::PubSub,
+        TabPanel::Memory,
+    ];
+}
+
+impl fmt::Display for TabPanel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TabPanel::Keys => "Keys",
+            TabPanel::Info => "Info",
+            TabPanel::Metrics => "Metrics",
+            TabPanel::Slowlog => "Slowlog",
+            TabPanel::Clients => "Clients",
+            TabPanel::Monitor => "Monitor",
+            TabPanel::PubSub => "Pub/Sub",
+            TabPanel::Memory => "Memory",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshInterval {
+    Off,
+    OneSecond,
+    FiveSeconds,
+    FifteenSeconds,
+    OneMinute,
+}
+
+impl RefreshInterval {
+    const ALL: [RefreshInterval; 5] = [
+        RefreshInterval::Off,
+        RefreshInterval::OneSecond,
+        RefreshInterval::FiveSeconds,
+        RefreshInterval::FifteenSeconds,
+        RefreshInterval::OneMinute,
+    ];
+
+    fn duration(&self) -> Option<Duration> {
+        match self {
+            RefreshInterval::Off => None,
+            RefreshInterval::OneSecond => Some(Duration::from_secs(1)),
+            RefreshInterval::FiveSeconds => Some(Duration::from_secs(5)),
+            RefreshInterval::FifteenSeconds => Some(Duration::from_secs(15)),
+            RefreshInterval::OneMinute => Some(Duration::from_secs(60)),
         }
+    }
+}
 
-        let sub_namespaces = redis_namespace
-            .sub_namespaces
-            .iter()
-            .map(|(_, ns)| NamespaceView::new(ns))
-            .collect();
+impl fmt::Display for RefreshInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RefreshInterval::Off => "Manual refresh",
+            RefreshInterval::OneSecond => "Every 1s",
+            RefreshInterval::FiveSeconds => "Every 5s",
+            RefreshInterval::FifteenSeconds => "Every 15s",
+            RefreshInterval::OneMinute => "Every 1m",
+        };
+        write!(f, "{}", name)
+    }
+}
 
-        NamespaceView {
-            namespace: redis_namespace.name.clone(),
-            expand_button_state: button::State::default(),
-            is_expanded: false,
-            sub_namespaces,
-            key_buttons,
+struct InfoState {
+    sections: Vec<InfoSection>,
+    interval: RefreshInterval,
+    interval_list_state: pick_list::State<RefreshInterval>,
+    refresh_button_state: button::State,
+    scrollbar_state: scrollable::State,
+}
+
+impl InfoState {
+    fn new() -> Self {
+        InfoState {
+            sections: Vec::<InfoSection>::new(),
+            interval: RefreshInterval::Off,
+            interval_list_state: pick_list::State::default(),
+            refresh_button_state: button::State::default(),
+            scrollbar_state: scrollable::State::default(),
         }
     }
+
+    fn refresh(&mut self, redis: &mut redis::Connection) -> redis::RedisResult<()> {
+        self.sections = get_server_info(redis)?;
+        Ok(())
+    }
 }
 
-impl RedisViewer {
-    fn refresh_keys(&mut self) {
-        let current_server_tab = self
-            .server_tabs
-            .get_mut(
-                self.current_server_tab_index
-                    .expect("failed to find current server tab index"),
-            )
-            .expect("failed to find current server tab in arena");
-        let keys = get_all_keys(&mut current_server_tab.redis).expect("failed to get keys");
-        let namespaces = convert_keys_to_namespaces(&keys);
-        let namespaces_view = create_namespace_views(&namespaces);
-        let mut key_buttons = Vec::<(String, button::State)>::new();
-        for key in keys.iter() {
-            key_buttons.push((key.clone(), button::State::default()));
+struct MetricsState {
+    history: MetricsHistory,
+    is_polling: bool,
+    is_paused: bool,
+    window: ChartWindow,
+    window_list_state: pick_list::State<ChartWindow>,
+    pause_button_state: button::State,
+}
+
+impl MetricsState {
+    fn new() -> Self {
+        MetricsState {
+            history: MetricsHistory::default(),
+            is_polling: false,
+            is_paused: false,
+            window: ChartWindow::FiveMinutes,
+            window_list_state: pick_list::State::default(),
+            pause_button_state: button::State::default(),
         }
+    }
 
-        current_server_tab.keys = keys;
-        current_server_tab.namespaces = namespaces;
-        current_server_tab.keys_scrollbar_state = KeysScrollbarState::default();
-        current_server_tab.key_buttons = key_buttons;
-        current_server_tab.namespaces_view = namespaces_view;
+    fn sample(&mut self, redis: &mut redis::Connection) -> redis::RedisResult<()> {
+        let sections = get_server_info(redis)?;
+        self.history.push(MetricSample::from_info(&sections));
+        Ok(())
     }
 }
 
-fn create_namespace_views(namespaces: &HashMap<String, RedisNamespace>) -> Vec<NamespaceView> {
-    let namespaces_view = namespaces
-        .iter()
-        .map(|(_, ns)| NamespaceView::new(ns))
-        .collect();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlowlogColumn {
+    Id,
+    Timestamp,
+    Duration,
+    Client,
+    Command,
+}
 
-    namespaces_view
+impl SlowlogColumn {
+    const ALL: [SlowlogColumn; 5] = [
+        SlowlogColumn::Id,
+        SlowlogColumn::Timestamp,
+        SlowlogColumn::Duration,
+        SlowlogColumn::Client,
+        SlowlogColumn::Command,
+    ];
+
+    fn width(&self) -> Length {
+        match self {
+            SlowlogColumn::Id => Length::FillPortion(1),
+            SlowlogColumn::Timestamp => Length::FillPortion(2),
+            SlowlogColumn::Duration => Length::FillPortion(1),
+            SlowlogColumn::Client => Length::FillPortion(2),
+            SlowlogColumn::Command => Length::FillPortion(5),
+        }
+    }
 }
 
-fn create_namespace_rows(namespace: &mut NamespaceView, indices: Vec<usize>) -> Row<Message> {
-    let expander_text = if namespace.is_expanded {
-        Text::new("^")
-    } else {
-        Text::new(">")
-    };
+impl fmt::Display for SlowlogColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SlowlogColumn::Id => "Id",
+            SlowlogColumn::Timestamp => "Time (UTC)",
+            SlowlogColumn::Duration => "Duration",
+            SlowlogColumn::Client => "Client",
+            SlowlogColumn::Command => "Command",
+        };
+        write!(f, "{}", name)
+    }
+}
 
-    let ns_row = Row::new()
-        .push(
-            Button::new(&mut namespace.expand_button_state, expander_text)
-                .padding(5)
-                .on_press(Message::NamespaceExpandToggle(indices.clone())),
-        )
-        .push(Text::new(namespace.namespace.clone()));
+struct SlowlogState {
+    entries: Vec<SlowlogEntry>,
+    count_value: String,
+    count_input_state: text_input::State,
+    fetch_button_state: button::State,
+    reset_button_state: button::State,
+    filter_value: String,
+    filter_input_state: text_input::State,
+    sort_column: SlowlogColumn,
+    sort_descending: bool,
+    sort_buttons: Vec<(SlowlogColumn, button::State)>,
+    threshold_value: String,
+    threshold_input_state: text_input::State,
+    threshold_button_state: button::State,
+    status: String,
+    scrollbar_state: scrollable::State,
+}
 
-    let column = Column::new().padding(indices.len() as u16 * 2).push(ns_row);
+impl SlowlogState {
+    fn new() -> Self {
+        SlowlogState {
+            entries: Vec::<SlowlogEntry>::new(),
+            count_value: DEFAULT_SLOWLOG_COUNT.to_string(),
+            count_input_state: text_input::State::default(),
+            fetch_button_state: button::State::default(),
+            reset_button_state: button::State::default(),
+            filter_value: String::new(),
+            filter_input_state: text_input::State::default(),
+            sort_column: SlowlogColumn::Id,
+            sort_descending: true,
+            sort_buttons: SlowlogColumn::ALL
+                .iter()
+                .map(|column| (*column, button::State::default()))
+                .collect(),
+            threshold_value: String::new(),
+            threshold_input_state: text_input::State::default(),
+            threshold_button_state: button::State::default(),
+            status: String::new(),
+            scrollbar_state: scrollable::State::default(),
+        }
+    }
 
-    let column = if namespace.is_expanded {
-        column
-            .push(namespace.sub_namespaces.iter_mut().enumerate().fold(
-                Column::new(),
-                |col, (i, sub_ns)| {
-                    let mut current_indices = indices.clone();
-                    current_indices.push(i);
-                    col.push(create_namespace_rows(sub_ns, current_indices))
-                },
-            ))
-            .push(Row::new().push(namespace.key_buttons.iter_mut().fold(
-                Column::new(),
-                |col, (key, state)| {
-                    col.push(
-                        Row::new().push(
-                            Button::new(state, Text::new(key.clone()))
-                                .padding(5)
-                                .on_press(Message::KeySelected(key.clone())),
-                        ),
-                    )
-                },
-            )))
-    } else {
-        column
-    };
+    fn fetch(&mut self, redis: &mut redis::Connection) -> redis::RedisResult<()> {
+        let count = self.count_value.parse().unwrap_or(DEFAULT_SLOWLOG_COUNT);
+        self.entries = get_slowlog(redis, count)?;
+        self.sort_entries();
+        Ok(())
+    }
+
+    fn load_threshold(&mut self, redis: &mut redis::Connection) {
+        match get_config_value(redis, SLOWLOG_THRESHOLD_CONFIG) {
+            Ok(value) => self.threshold_value = value.unwrap_or_default(),
+            Err(e) => self.status = format!("Failed to read {}: {}", SLOWLOG_THRESHOLD_CONFIG, e),
+        }
+    }
 
-    Row::new().push(column)
+    fn sort_entries(&mut self) {
+        match self.sort_column {
+            SlowlogColumn::Id => self.entries.sort_by_key(|entry| entry.id),
+            SlowlogColumn::Timestamp => self.entries.sort_by_key(|entry| entry.timestamp),
+            SlowlogColumn::Duration => self.entries.sort_by_key(|entry| entry.duration_micros),
+            SlowlogColumn::Client => self
+                .entries
+                .sort_by(|a, b| a.client_addr.cmp(&b.client_addr)),
+            SlowlogColumn::Command => self.entries.sort_by(|a, b| a.command.cmp(&b.command)),
+        }
+        if self.sort_descending {
+            self.entries.reverse();
+        }
+    }
 }
 
-impl Application for RedisViewer {
-    type Message = Message;
-    type Executor = executor::Default;
-    type Flags = ();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientColumn {
+    Id,
+    Addr,
+    Name,
+    Age,
+    Idle,
+    Db,
+    Cmd,
+    Memory,
+}
 
-    fn new(_flags: ()) -> (Self, Command<Message>) {
-        let server_tabs = Arena::<ServerTab>::new();
+impl ClientColumn {
+    const ALL: [ClientColumn; 8] = [
+        ClientColumn::Id,
+        ClientColumn::Addr,
+        ClientColumn::Name,
+        ClientColumn::Age,
+        ClientColumn::Idle,
+        ClientColumn::Db,
+        ClientColumn::Cmd,
+        ClientColumn::Memory,
+    ];
 
-        let current_server_tab_index = None;
+    fn width(&self) -> Length {
+        match self {
+            ClientColumn::Addr | ClientColumn::Name => Length::FillPortion(3),
+            ClientColumn::Age | ClientColumn::Idle | ClientColumn::Cmd => Length::FillPortion(2),
+            ClientColumn::Memory => Length::FillPortion(2),
+            ClientColumn::Id | ClientColumn::Db => Length::FillPortion(1),
+        }
+    }
+}
 
-        let conn_form_state = ConnectionFormState {
-            show_connection_form: true,
-            conn_name_text_input_state: text_input::State::default(),
-            conn_name_value: String::from("localhost"),
-            conn_text_input_state: text_input::State::default(),
-            conn_value: String::from("127.0.0.1"),
-            connect_button: button::State::default(),
-            port_text_input_state: text_input::State::default(),
-            port_value: String::from("6379"),
-            db_text_input_state: text_input::State::default(),
-            db_value: String::from("0"),
+impl fmt::Display for ClientColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ClientColumn::Id => "Id",
+            ClientColumn::Addr => "Address",
+            ClientColumn::Name => "Name",
+            ClientColumn::Age => "Age",
+            ClientColumn::Idle => "Idle",
+            ClientColumn::Db => "Db",
+            ClientColumn::Cmd => "Last command",
+            ClientColumn::Memory => "Memory",
         };
+        write!(f, "{}", name)
+    }
+}
 
-        let keys_refresh_button_state = button::State::default();
-        let tab_buttons = Vec::<(String, Index, button::State)>::new();
-        let new_tab_button = button::State::default();
-        let create_key_button = button::State::default();
+struct ClientsState {
+    clients: Vec<ClientInfo>,
+    own_id: Option<u64>,
+    row_buttons: Vec<(u64, button::State)>,
+    selected: Option<u64>,
+    is_confirming_kill: bool,
+    filter_value: String,
+    filter_input_state: text_input::State,
+    sort_column: ClientColumn,
+    sort_descending: bool,
+    sort_buttons: Vec<(ClientColumn, button::State)>,
+    refresh_button_state: button::State,
+    kill_button_state: button::State,
+    confirm_button_state: button::State,
+    cancel_button_state: button::State,
+    status: String,
+    scrollbar_state: scrollable::State,
+}
 
-        (
-            Self {
-                server_tabs,
-                current_server_tab_index,
-                conn_form_state,
-                keys_refresh_button_state,
-                tab_buttons,
-                new_tab_button,
-                create_key_button,
-            },
-            Command::none(),
-        )
+impl ClientsState {
+    fn new() -> Self {
+        ClientsState {
+            clients: Vec::<ClientInfo>::new(),
+            own_id: None,
+            row_buttons: Vec::<(u64, button::State)>::new(),
+            selected: None,
+            is_confirming_kill: false,
+            filter_value: String::new(),
+            filter_input_state: text_input::State::default(),
+            sort_column: ClientColumn::Id,
+            sort_descending: false,
+            sort_buttons: ClientColumn::ALL
+                .iter()
+                .map(|column| (*column, button::State::default()))
+                .collect(),
+            refresh_button_state: button::State::default(),
+            kill_button_state: button::State::default(),
+            confirm_button_state: button::State::default(),
+            cancel_button_state: button::State::default(),
+            status: String::new(),
+            scrollbar_state: scrollable::State::default(),
+        }
     }
 
-    fn title(&self) -> String {
-        String::from("Icy Redis Viewer")
+    fn refresh(&mut self, redis: &mut redis::Connection) -> redis::RedisResult<()> {
+        if self.own_id.is_none() {
+            self.own_id = get_client_id(redis).ok();
+        }
+        self.clients = get_client_list(redis)?;
+        self.sort_clients();
+        if !self
+            .clients
+            .iter()
+            .any(|client| Some(client.id) == self.selected)
+        {
+            self.selected = None;
+            self.is_confirming_kill = false;
+        }
+        Ok(())
     }
 
-    fn update(&mut self, message: Self::Message, _clipboard: &mut Clipboard) -> Command<Message> {
-        match message {
-            Message::KeySelected(key) => {
-                let current_server_tab = self
-                    .server_tabs
-                    .get_mut(
-                        self.current_server_tab_index
-                            .expect("failed to find current server tab index"),
-                    )
-                    .expect("failed to find current server tab in arena");
-                let value = get_redis_value(&mut current_server_tab.redis, &key)
-                    .expect("failed to get value for selected redis key");
-                match value {
-                    RedisValue::String(s) => {
-                        current_server_tab.editor_state = EditorState::Edit(ValueEditState {
-                            key: key.clone(),
-                            value: s,
-                            ..default()
-                        });
-                    }
-                    _ => {
-                        current_server_tab.editor_state = EditorState::Empty;
-                    }
-                }
-            }
-            Message::SelectedValueChanged(s) => {
-                let current_server_tab = self
-                    .server_tabs
-                    .get_mut(
-                        self.current_server_tab_index
-                            .expect("failed to find current server tab index"),
-                    )
-                    .expect("failed to find current server tab in arena");
-                match &mut current_server_tab.editor_state {
-                    EditorState::Empty => {}
-                    EditorState::Edit(edit_state) => {
-                        edit_state.value = s;
+    fn sort_clients(&mut self) {
+        match self.sort_column {
+            ClientColumn::Id => self.clients.sort_by_key(|client| client.id),
+            ClientColumn::Addr => self.clients.sort_by(|a, b| a.addr.cmp(&b.addr)),
+            ClientColumn::Name => self.clients.sort_by(|a, b| a.name.cmp(&b.name)),
+            ClientColumn::Age => self.clients.sort_by_key(|client| client.age),
+            ClientColumn::Idle => self.clients.sort_by_key(|client| client.idle),
+            ClientColumn::Db => self.clients.sort_by_key(|client| client.db),
+            ClientColumn::Cmd => self.clients.sort_by(|a, b| a.cmd.cmp(&b.cmd)),
+            ClientColumn::Memory => self.clients.sort_by_key(|client| client.memory),
+        }
+        if self.sort_descending {
+            self.clients.reverse();
+        }
+        self.row_buttons = self
+            .clients
+            .iter()
+            .map(|client| (client.id, button::State::default()))
+            .collect();
+    }
+}
+
+struct MonitorState {
+    entries: VecDeque<MonitorEntry>,
+    is_running: bool,
+    is_paused: bool,
+    command_filter_value: String,
+    command_filter_input_state: text_input::State,
+    key_filter_value: String,
+    key_filter_input_state: text_input::State,
+    client_filter_value: String,
+    client_filter_input_state: text_input::State,
+    export_path_value: String,
+    export_path_input_state: text_input::State,
+    start_button_state: button::State,
+    pause_button_state: button::State,
+    clear_button_state: button::State,
+    export_button_state: button::State,
+    status: String,
+    scrollbar_state: scrollable::State,
+}
+
+impl MonitorState {
+    fn new() -> Self {
+        MonitorState {
+            entries: VecDeque::<MonitorEntry>::new(),
+            is_running: false,
+            is_paused: false,
+            command_filter_value: String::new(),
+            command_filter_input_state: text_input::State::default(),
+            key_filter_value: String::new(),
+            key_filter_input_state: text_input::State::default(),
+            client_filter_value: String::new(),
+            client_filter_input_state: text_input::State::default(),
+            export_path_value: String::from("monitor.log"),
+            export_path_input_state: text_input::State::default(),
+            start_button_state: button::State::default(),
+            pause_button_state: button::State::default(),
+            clear_button_state: button::State::default(),
+            export_button_state: button::State::default(),
+            status: String::new(),
+            scrollbar_state: scrollable::State::default(),
+        }
+    }
+
+    fn push(&mut self, entry: MonitorEntry) {
+        if self.is_paused {
+            return;
+        }
+        self.entries.push_back(entry);
+        while self.entries.len() > MAX_MONITOR_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+
+    fn is_visible(&self, entry: &MonitorEntry) -> bool {
+        let command_filter = self.command_filter_value.to_lowercase();
+        let client_filter = self.client_filter_value.to_lowercase();
+
+        (command_filter.is_empty() || entry.command.to_lowercase().contains(&command_filter))
+            && (self.key_filter_value.is_empty()
+                || entry
+                    .args
+                    .first()
+                    .is_some_and(|key| glob_match(&self.key_filter_value, key)))
+            && (client_filter.is_empty() || entry.client.to_lowercase().contains(&client_filter))
+    }
+
+    fn export(&self) -> std::io::Result<usize> {
+        let mut contents = String::new();
+        for entry in self.entries.iter() {
+            contents.push_str(&entry.raw);
+            contents.push('\n');
+        }
+        fs::write(&self.export_path_value, contents)?;
+        Ok(self.entries.len())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryPrecision {
+    Sampled,
+    Full,
+}
+
+impl MemoryPrecision {
+    const ALL: [MemoryPrecision; 2] = [MemoryPrecision::Sampled, MemoryPrecision::Full];
+
+    /// The SAMPLES option of MEMORY USAGE, where 0 means every element.
+    fn samples(&self) -> usize {
+        match self {
+            MemoryPrecision::Sampled => MEMORY_USAGE_SAMPLES,
+            MemoryPrecision::Full => 0,
+        }
+    }
+}
+
+impl fmt::Display for MemoryPrecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryPrecision::Sampled => write!(f, "Sampled ({} elements)", MEMORY_USAGE_SAMPLES),
+            MemoryPrecision::Full => write!(f, "Full (every element)"),
+        }
+    }
+}
+
+struct MemoryState {
+    precision: MemoryPrecision,
+    precision_list_state: pick_list::State<MemoryPrecision>,
+    top_value: String,
+    top_input_state: text_input::State,
+    scan_button_state: button::State,
+    cancel_button_state: button::State,
+    is_running: bool,
+    usage: Vec<KeyMemory>,
+    /// Names in `usage`, as SCAN may return a key more than once.
+    measured_keys: HashSet<String>,
+    namespaces: Vec<NamespaceMemory>,
+    top_keys: Vec<KeyMemory>,
+    tree: Vec<MemoryTreeNode>,
+    show_treemap: bool,
+    treemap_button_state: button::State,
+    /// The namespace the treemap is drilled down into.
+    treemap_path: Vec<String>,
+    treemap_up_button_state: button::State,
+    namespaces_scrollbar_state: scrollable::State,
+    top_keys_scrollbar_state: scrollable::State,
+    status: String,
+}
+
+impl MemoryState {
+    fn new() -> Self {
+        MemoryState {
+            precision: MemoryPrecision::Sampled,
+            precision_list_state: pick_list::State::default(),
+            top_value: DEFAULT_TOP_KEYS.to_string(),
+            top_input_state: text_input::State::default(),
+            scan_button_state: button::State::default(),
+            cancel_button_state: button::State::default(),
+            is_running: false,
+            usage: Vec::<KeyMemory>::new(),
+            measured_keys: HashSet::<String>::new(),
+            namespaces: Vec::<NamespaceMemory>::new(),
+            top_keys: Vec::<KeyMemory>::new(),
+            tree: Vec::<MemoryTreeNode>::new(),
+            show_treemap: false,
+            treemap_button_state: button::State::default(),
+            treemap_path: Vec::<String>::new(),
+            treemap_up_button_state: button::State::default(),
+            namespaces_scrollbar_state: scrollable::State::default(),
+            top_keys_scrollbar_state: scrollable::State::default(),
+            status: String::new(),
+        }
+    }
+
+    fn start(&mut self) {
+        self.is_running = true;
+        self.usage.clear();
+        self.measured_keys.clear();
+        self.namespaces.clear();
+        self.top_keys.clear();
+        self.tree.clear();
+        self.treemap_path.clear();
+        self.status = "Scanning...".into();
+    }
+
+    fn add_usage(&mut self, measured: Vec<KeyMemory>) {
+        for key_memory in measured {
+            if self.measured_keys.insert(key_memory.key.clone()) {
+                self.usage.push(key_memory);
+            }
+        }
+    }
+
+    fn total_bytes(&self) -> u64 {
+        self.usage.iter().map(|key_memory| key_memory.bytes).sum()
+    }
+
+    fn top_count(&self) -> usize {
+        self.top_value.trim().parse().unwrap_or(DEFAULT_TOP_KEYS)
+    }
+
+    /// Aggregates what was measured so far, also after a cancel or failure.
+    fn summarize(&mut self, delimiters: &str) {
+        self.is_running = false;
+        self.namespaces = aggregate_namespaces(&self.usage, delimiters);
+        self.top_keys = top_keys(&self.usage, self.top_count());
+        self.tree = build_memory_tree(&self.usage, delimiters);
+        self.treemap_path.clear();
+    }
+}
+
+struct PubSubState {
+    channels: Vec<(String, button::State)>,
+    patterns: Vec<(String, button::State)>,
+    messages: VecDeque<PubSubMessage>,
+    subscribe_value: String,
+    subscribe_input_state: text_input::State,
+    subscribe_button_state: button::State,
+    psubscribe_button_state: button::State,
+    clear_button_state: button::State,
+    publish_channel_value: String,
+    publish_channel_input_state: text_input::State,
+    publish_message_value: String,
+    publish_message_input_state: text_input::State,
+    publish_button_state: button::State,
+    active_channels: Vec<(String, u64)>,
+    list_pattern_value: String,
+    list_pattern_input_state: text_input::State,
+    list_button_state: button::State,
+    status: String,
+    generation: u32,
+    retry_at: Option<Instant>,
+    messages_scrollbar_state: scrollable::State,
+    channels_scrollbar_state: scrollable::State,
+}
+
+impl PubSubState {
+    fn new() -> Self {
+        PubSubState {
+            channels: Vec::<(String, button::State)>::new(),
+            patterns: Vec::<(String, button::State)>::new(),
+            messages: VecDeque::<PubSubMessage>::new(),
+            subscribe_value: String::new(),
+            subscribe_input_state: text_input::State::default(),
+            subscribe_button_state: button::State::default(),
+            psubscribe_button_state: button::State::default(),
+            clear_button_state: button::State::default(),
+            publish_channel_value: String::new(),
+            publish_channel_input_state: text_input::State::default(),
+            publish_message_value: String::new(),
+            publish_message_input_state: text_input::State::default(),
+            publish_button_state: button::State::default(),
+            active_channels: Vec::<(String, u64)>::new(),
+            list_pattern_value: String::from("*"),
+            list_pattern_input_state: text_input::State::default(),
+            list_button_state: button::State::default(),
+            status: String::new(),
+            generation: 0,
+            retry_at: None,
+            messages_scrollbar_state: scrollable::State::default(),
+            channels_scrollbar_state: scrollable::State::default(),
+        }
+    }
+
+    fn is_subscribed(&self) -> bool {
+        !self.channels.is_empty() || !self.patterns.is_empty()
+    }
+
+    /// Starts a new subscription once the delay after a failure has passed.
+    fn retry_if_due(&mut self) {
+        match self.retry_at {
+            Some(retry_at) if retry_at <= Instant::now() => {
+                self.retry_at = None;
+                if self.is_subscribed() {
+                    self.generation = self.generation.wrapping_add(1);
+                    self.status = "Subscribing again".into();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push(&mut self, message: PubSubMessage) {
+        self.messages.push_back(message);
+        while self.messages.len() > MAX_PUBSUB_MESSAGES {
+            self.messages.pop_front();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyTypeFilter {
+    All,
+    String,
+    List,
+    Set,
+    ZSet,
+    Hash,
+    Stream,
+    Json,
+}
+
+impl KeyTypeFilter {
+    const ALL: [KeyTypeFilter; 8] = [
+        KeyTypeFilter::All,
+        KeyTypeFilter::String,
+        KeyTypeFilter::List,
+        KeyTypeFilter::Set,
+        KeyTypeFilter::ZSet,
+        KeyTypeFilter::Hash,
+        KeyTypeFilter::Stream,
+        KeyTypeFilter::Json,
+    ];
+
+    /// The name TYPE reports for keys matching this filter.
+    fn type_name(&self) -> Option<&'static str> {
+        match self {
+            KeyTypeFilter::All => None,
+            KeyTypeFilter::String => Some("string"),
+            KeyTypeFilter::List => Some("list"),
+            KeyTypeFilter::Set => Some("set"),
+            KeyTypeFilter::ZSet => Some("zset"),
+            KeyTypeFilter::Hash => Some("hash"),
+            KeyTypeFilter::Stream => Some("stream"),
+            KeyTypeFilter::Json => Some("ReJSON-RL"),
+        }
+    }
+}
+
+impl fmt::Display for KeyTypeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.type_name() {
+            Some(type_name) => write!(f, "{}", type_badge(type_name)),
+            None => write!(f, "All types"),
+        }
+    }
+}
+
+/// Short label for a TYPE reply, naming module types by what they hold.
+fn type_badge(key_type: &str) -> &str {
+    match key_type {
+        "ReJSON-RL" => "json",
+        "none" => "",
+        _ => key_type,
+    }
+}
+
+/// The databases of the server and the controls to switch the tab to
+/// another one.
+struct KeyspaceState {
+    databases: Vec<KeyspaceDb>,
+    db_list_state: pick_list::State<KeyspaceDb>,
+    db_value: String,
+    db_input_state: text_input::State,
+    select_button_state: button::State,
+    status: String,
+}
+
+impl KeyspaceState {
+    fn new() -> Self {
+        KeyspaceState {
+            databases: Vec::<KeyspaceDb>::new(),
+            db_list_state: pick_list::State::default(),
+            db_value: String::new(),
+            db_input_state: text_input::State::default(),
+            select_button_state: button::State::default(),
+            status: String::new(),
+        }
+    }
+}
+
+/// How keys are split into namespaces and ordered. `delimiters` is what the
+/// tree was built with, `delimiters_value` is the pending edit.
+struct KeyGroupingState {
+    delimiters: String,
+    delimiters_value: String,
+    delimiters_input_state: text_input::State,
+    apply_button_state: button::State,
+    sort_descending: bool,
+    sort_button_state: button::State,
+    type_filter: KeyTypeFilter,
+    type_filter_list_state: pick_list::State<KeyTypeFilter>,
+}
+
+impl KeyGroupingState {
+    fn new(delimiters: String) -> Self {
+        KeyGroupingState {
+            delimiters_value: delimiters.clone(),
+            delimiters,
+            delimiters_input_state: text_input::State::default(),
+            apply_button_state: button::State::default(),
+            sort_descending: false,
+            sort_button_state: button::State::default(),
+            type_filter: KeyTypeFilter::All,
+            type_filter_list_state: pick_list::State::default(),
+        }
+    }
+}
+
+fn compare_names(a: &str, b: &str, descending: bool) -> Ordering {
+    let order = natural_cmp(a, b);
+    if descending {
+        order.reverse()
+    } else {
+        order
+    }
+}
+
+/// Inserts `key` at its sorted position, unless it is already listed.
+/// Returns whether it was added.
+fn insert_sorted_key(keys: &mut Vec<String>, key: &str, descending: bool) -> bool {
+    match keys.binary_search_by(|k| compare_names(k, key, descending)) {
+        Ok(_) => false,
+        Err(index) => {
+            keys.insert(index, key.into());
+            true
+        }
+    }
+}
+
+fn remove_sorted_key(keys: &mut Vec<String>, key: &str, descending: bool) -> bool {
+    match keys.binary_search_by(|k| compare_names(k, key, descending)) {
+        Ok(index) => {
+            keys.remove(index);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+fn sort_keys(keys: &mut [String], descending: bool) {
+    keys.sort_by(|a, b| compare_names(a, b, descending));
+}
+
+/// Keys ticked in the tree for bulk actions. `anchor` is the last key
+/// clicked, where a shift-click range starts.
+struct KeySelectionState {
+    selected: HashSet<String>,
+    anchor: Option<String>,
+    is_confirming_delete: bool,
+    delete_button_state: button::State,
+    clear_button_state: button::State,
+    confirm_button_state: button::State,
+    cancel_button_state: button::State,
+    preview_scrollbar_state: scrollable::State,
+    status: String,
+}
+
+impl KeySelectionState {
+    fn new() -> Self {
+        KeySelectionState {
+            selected: HashSet::<String>::new(),
+            anchor: None,
+            is_confirming_delete: false,
+            delete_button_state: button::State::default(),
+            clear_button_state: button::State::default(),
+            confirm_button_state: button::State::default(),
+            cancel_button_state: button::State::default(),
+            preview_scrollbar_state: scrollable::State::default(),
+            status: String::new(),
+        }
+    }
+
+    fn toggle(&mut self, key: String, is_selected: bool) {
+        if is_selected {
+            self.selected.insert(key.clone());
+        } else {
+            self.selected.remove(&key);
+        }
+        self.anchor = Some(key);
+    }
+}
+
+/// Deletes every key matching a glob from a background scan. Deleting needs
+/// a finished dry run of the same patterns, which `preview` holds. A
+/// namespace row fills in `namespace_patterns` until the pattern is edited.
+struct PatternDeleteState {
+    pattern_value: String,
+    namespace_patterns: Vec<String>,
+    pattern_input_state: text_input::State,
+    preview_button_state: button::State,
+    delete_button_state: button::State,
+    cancel_button_state: button::State,
+    running_patterns: Option<Vec<String>>,
+    is_dry_run: bool,
+    progress: PatternDeleteProgress,
+    preview: Option<(Vec<String>, PatternDeleteProgress)>,
+    status: String,
+}
+
+impl PatternDeleteState {
+    fn new() -> Self {
+        PatternDeleteState {
+            pattern_value: String::new(),
+            namespace_patterns: Vec::new(),
+            pattern_input_state: text_input::State::default(),
+            preview_button_state: button::State::default(),
+            delete_button_state: button::State::default(),
+            cancel_button_state: button::State::default(),
+            running_patterns: None,
+            is_dry_run: true,
+            progress: PatternDeleteProgress::default(),
+            preview: None,
+            status: String::new(),
+        }
+    }
+
+    fn patterns(&self) -> Vec<String> {
+        if self.namespace_patterns.is_empty() {
+            vec![self.pattern_value.clone()]
+        } else {
+            self.namespace_patterns.clone()
+        }
+    }
+
+    fn set_namespace_patterns(&mut self, patterns: Vec<String>) {
+        self.pattern_value = patterns.join(", ");
+        self.namespace_patterns = patterns;
+    }
+
+    fn start(&mut self, dry_run: bool) {
+        self.running_patterns = Some(self.patterns());
+        self.is_dry_run = dry_run;
+        self.progress = PatternDeleteProgress::default();
+        self.preview = None;
+        self.status = if dry_run {
+            "Scanning...".into()
+        } else {
+            "Deleting...".into()
+        };
+    }
+}
+
+struct KeysExportState {
+    format: FileFormat,
+    format_list_state: pick_list::State<FileFormat>,
+    path_value: String,
+    path_input_state: text_input::State,
+    selected_button_state: button::State,
+    pattern_button_state: button::State,
+    status: String,
+}
+
+impl KeysExportState {
+    fn new() -> Self {
+        KeysExportState {
+            format: FileFormat::Json,
+            format_list_state: pick_list::State::default(),
+            path_value: String::from(FileFormat::Json.default_path()),
+            path_input_state: text_input::State::default(),
+            selected_button_state: button::State::default(),
+            pattern_button_state: button::State::default(),
+            status: String::new(),
+        }
+    }
+}
+
+/// Loads a JSON snapshot into the tab's database. The file is read and
+/// checked against the database on preview, and only a previewed snapshot
+/// can be imported.
+struct KeysImportState {
+    format: FileFormat,
+    format_list_state: pick_list::State<FileFormat>,
+    path_value: String,
+    path_input_state: text_input::State,
+    policy: ConflictPolicy,
+    policy_list_state: pick_list::State<ConflictPolicy>,
+    suffix_value: String,
+    suffix_input_state: text_input::State,
+    preview_button_state: button::State,
+    import_button_state: button::State,
+    snapshot: Option<(KeysSnapshot, ImportPreview)>,
+    report: Option<ImportReport>,
+    /// A parsed redis-cli script or RESP file, run as is.
+    commands: Option<Vec<RedisCommand>>,
+    commands_report: Option<CommandsReport>,
+    status: String,
+}
+
+impl KeysImportState {
+    fn new() -> Self {
+        KeysImportState {
+            format: FileFormat::Json,
+            format_list_state: pick_list::State::default(),
+            path_value: String::from(FileFormat::Json.default_path()),
+            path_input_state: text_input::State::default(),
+            policy: ConflictPolicy::Skip,
+            policy_list_state: pick_list::State::default(),
+            suffix_value: String::from(":imported"),
+            suffix_input_state: text_input::State::default(),
+            preview_button_state: button::State::default(),
+            import_button_state: button::State::default(),
+            snapshot: None,
+            report: None,
+            commands: None,
+            commands_report: None,
+            status: String::new(),
+        }
+    }
+}
+
+/// Where copied keys go: another open tab, or another db of the tab's own
+/// server.
+#[derive(Debug, Clone)]
+pub enum CopyTarget {
+    Tab(Index, String),
+    Database,
+}
+
+impl PartialEq for CopyTarget {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (CopyTarget::Tab(a, _), CopyTarget::Tab(b, _)) => a == b,
+            (CopyTarget::Database, CopyTarget::Database) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for CopyTarget {}
+
+impl fmt::Display for CopyTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CopyTarget::Tab(_, name) => write!(f, "Tab {}", name),
+            CopyTarget::Database => write!(f, "Another db on this server"),
+        }
+    }
+}
+
+struct KeysCopyState {
+    target: Option<CopyTarget>,
+    target_list_state: pick_list::State<CopyTarget>,
+    db_value: String,
+    db_input_state: text_input::State,
+    replace: bool,
+    copy_button_state: button::State,
+    report: Vec<(String, Result<(), String>)>,
+    report_scrollbar_state: scrollable::State,
+    status: String,
+}
+
+impl KeysCopyState {
+    fn new() -> Self {
+        KeysCopyState {
+            target: None,
+            target_list_state: pick_list::State::default(),
+            db_value: String::new(),
+            db_input_state: text_input::State::default(),
+            replace: false,
+            copy_button_state: button::State::default(),
+            report: Vec::<(String, Result<(), String>)>::new(),
+            report_scrollbar_state: scrollable::State::default(),
+            status: String::new(),
+        }
+    }
+}
+
+struct LiveKeysState {
+    is_enabled: bool,
+    configure_notifications: bool,
+    toggle_button_state: button::State,
+    status: String,
+}
+
+impl LiveKeysState {
+    fn new() -> Self {
+        LiveKeysState {
+            is_enabled: false,
+            configure_notifications: false,
+            toggle_button_state: button::State::default(),
+            status: String::new(),
+        }
+    }
+}
+
+struct ConnectionFormState {
+    show_connection_form: bool,
+    conn_name_text_input_state: text_input::State,
+    conn_name_value: String,
+    conn_text_input_state: text_input::State,
+    conn_value: String,
+    port_text_input_state: text_input::State,
+    port_value: String,
+    db_text_input_state: text_input::State,
+    db_value: String,
+    delimiters_text_input_state: text_input::State,
+    delimiters_value: String,
+    connect_timeout_text_input_state: text_input::State,
+    connect_timeout_value: String,
+    read_timeout_text_input_state: text_input::State,
+    read_timeout_value: String,
+    write_timeout_text_input_state: text_input::State,
+    write_timeout_value: String,
+    connect_button: button::State,
+    test_button: button::State,
+    rdb_path_text_input_state: text_input::State,
+    rdb_path_value: String,
+    open_rdb_button: button::State,
+    status: String,
+}
+
+impl ConnectionFormState {
+    fn settings(&self) -> Result<ConnectionSettings, String> {
+        let port = self
+            .port_value
+            .trim()
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid port {}", self.port_value))?;
+        let db = self
+            .db_value
+            .trim()
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid db {}", self.db_value))?;
+        let connect_timeout = parse_timeout("connect", &self.connect_timeout_value)?
+            .ok_or("The connect timeout must be more than 0 ms")?;
+        Ok(ConnectionSettings {
+            address: self.conn_value.trim().to_string(),
+            port,
+            db,
+            connect_timeout,
+            read_timeout: parse_timeout("read", &self.read_timeout_value)?,
+            write_timeout: parse_timeout("write", &self.write_timeout_value)?,
+        })
+    }
+}
+
+/// Reads a timeout in milliseconds, where nothing or 0 means no timeout.
+fn parse_timeout(name: &str, value: &str) -> Result<Option<Duration>, String> {
+    match value.trim() {
+        "" => Ok(None),
+        millis => match millis.parse::<u64>() {
+            Ok(0) => Ok(None),
+            Ok(millis) => Ok(Some(Duration::from_millis(millis))),
+            Err(_) => Err(format!("Invalid {} timeout {}", name, value)),
+        },
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    KeySelected(String),
+    SelectedValueChanged(String),
+    SelectedValueSaved,
+    SelectedValueReloaded,
+    SelectedValueDeleted,
+    ConnNameChanged(String),
+    ConnValueChanged(String),
+    PortValueChanged(String),
+    DbValueChanged(String),
+    DelimitersValueChanged(String),
+    TabDelimitersChanged(String),
+    ApplyTabDelimiters,
+    ToggleKeySort,
+    KeyTypeFilterChanged(KeyTypeFilter),
+    ModifiersChanged(keyboard::Modifiers),
+    KeyChecked(String, bool),
+    ClearKeySelection,
+    DeleteSelectedKeys,
+    ConfirmDeleteSelectedKeys,
+    CancelDeleteSelectedKeys,
+    DeletePatternChanged(String),
+    PreviewPatternDelete,
+    RunPatternDelete,
+    CancelPatternDelete,
+    DeleteNamespace(Vec<usize>),
+    PatternDeleteEventReceived(Index, PatternDeleteEvent),
+    ExportFormatChanged(FileFormat),
+    ExportPathChanged(String),
+    ExportSelectedKeys,
+    ExportPatternKeys,
+    ExportNamespace(Vec<usize>),
+    ImportFormatChanged(FileFormat),
+    ImportPathChanged(String),
+    ImportPolicyChanged(ConflictPolicy),
+    ImportSuffixChanged(String),
+    PreviewImport,
+    RunImport,
+    CopyTargetChanged(CopyTarget),
+    CopyDbChanged(String),
+    CopyReplaceToggled(bool),
+    CopySelectedKeys,
+    CopyNamespace(Vec<usize>),
+    ConnectRedis,
+    TestConnection,
+    ConnectTimeoutChanged(String),
+    ReadTimeoutChanged(String),
+    WriteTimeoutChanged(String),
+    DbPicked(KeyspaceDb),
+    SwitchDbValueChanged(String),
+    SwitchDb,
+    RdbPathChanged(String),
+    OpenRdb,
+    RefreshKeys,
+    ChangeTab(Index),
+    CloseTab(Index),
+    MoveTabLeft(Index),
+    MoveTabRight(Index),
+    StartRenameTab(Index),
+    TabNameChanged(String),
+    RenameTab,
+    NewTab,
+    OpenCreateKeyForm,
+    CreateKey,
+    CreateKeyChanged(String),
+    CreateValueChanged(String),
+    NamespaceExpandToggle(Vec<usize>),
+    ShowMoreKeys(Vec<usize>),
+    KeyListScrolled,
+    MouseDragChanged(bool),
+    ChangePanel(TabPanel),
+    RefreshInfo,
+    InfoIntervalChanged(RefreshInterval),
+    MetricsPauseToggled,
+    MetricsWindowChanged(ChartWindow),
+    PanelTick(Index, TabPanel),
+    HealthTick(Index),
+    SlowlogCountChanged(String),
+    FetchSlowlog,
+    ResetSlowlog,
+    SlowlogFilterChanged(String),
+    SlowlogSortChanged(SlowlogColumn),
+    SlowlogThresholdChanged(String),
+    SaveSlowlogThreshold,
+    RefreshClients,
+    ClientsFilterChanged(String),
+    ClientsSortChanged(ClientColumn),
+    ClientSelected(u64),
+    KillClient,
+    ConfirmKillClient,
+    CancelKillClient,
+    ToggleMonitor,
+    ToggleMonitorPause,
+    ClearMonitor,
+    MonitorCommandFilterChanged(String),
+    MonitorKeyFilterChanged(String),
+    MonitorClientFilterChanged(String),
+    MonitorExportPathChanged(String),
+    ExportMonitor,
+    MonitorEventReceived(Index, MonitorEvent),
+    ToggleLiveKeys,
+    ConfigureNotificationsToggled(bool),
+    KeyspaceEventReceived(Index, KeyspaceEvent),
+    PubSubSubscribeChanged(String),
+    SubscribeChannel,
+    SubscribePattern,
+    UnsubscribeChannel(String),
+    UnsubscribePattern(String),
+    ClearPubSubMessages,
+    PublishChannelChanged(String),
+    PublishMessageChanged(String),
+    PublishMessage,
+    PubSubListPatternChanged(String),
+    ListPubSubChannels,
+    PubSubEventReceived(Index, PubSubEvent),
+    MemoryPrecisionChanged(MemoryPrecision),
+    MemoryTopChanged(String),
+    StartMemoryScan,
+    CancelMemoryScan,
+    MemoryScanEventReceived(Index, MemoryScanEvent),
+    KeyScanEventReceived(Index, KeyScanEvent),
+    ToggleMemoryTreemap,
+    MemoryTreemapDrillDown(String),
+    MemoryTreemapUp,
+    OpenMemoryKey(String),
+}
+
+#[derive(Debug, Clone)]
+enum EditorState {
+    Empty,
+    Edit(ValueEditState),
+    Create(KeyCreateState),
+    Inspect(ValueInspectState),
+}
+
+impl EditorState {
+    /// The key shown in the editor, if any.
+    fn key(&self) -> Option<&str> {
+        match self {
+            EditorState::Edit(state) => Some(&state.key),
+            EditorState::Inspect(state) => Some(&state.key),
+            EditorState::Empty | EditorState::Create(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct ValueEditState {
+    key: String,
+    value: String,
+    /// The value as last read or saved, to tell whether the user typed.
+    saved_value: String,
+    value_input_state: text_input::State,
+    save_button_state: button::State,
+    reload_button_state: button::State,
+    delete_button_state: button::State,
+}
+
+impl ValueEditState {
+    fn is_modified(&self) -> bool {
+        self.value != self.saved_value
+    }
+}
+
+/// A value the editor can't change, one line per element. Read-only tabs
+/// show every value this way, server tabs those that aren't strings.
+#[derive(Debug, Clone, Default)]
+struct ValueInspectState {
+    key: String,
+    lines: Vec<String>,
+    scrollbar_state: scrollable::State,
+}
+
+impl ValueInspectState {
+    fn new(key: String, value: RedisValue) -> Self {
+        let lines = match value {
+            RedisValue::String(value) => vec![value],
+            RedisValue::List(items) => items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| format!("{}) {}", i + 1, item))
+                .collect(),
+            RedisValue::Set(mut members) => {
+                members.sort_by(|a, b| natural_cmp(a, b));
+                members
+            }
+            RedisValue::ZSet(members) => members
+                .into_iter()
+                .map(|(member, score)| format!("{}  {}", score, member))
+                .collect(),
+            RedisValue::Hash(fields) => {
+                let mut fields: Vec<(String, String)> = fields.into_iter().collect();
+                fields.sort_by(|(a, _), (b, _)| natural_cmp(a, b));
+                fields
+                    .into_iter()
+                    .map(|(field, value)| format!("{}: {}", field, value))
+                    .collect()
+            }
+            RedisValue::Null => vec![String::from("Values of this type can't be shown.")],
+        };
+        ValueInspectState {
+            key,
+            lines,
+            ..default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct KeyCreateState {
+    key: String,
+    key_input_state: text_input::State,
+    value: String,
+    value_input_state: text_input::State,
+    create_button_state: button::State,
+}
+
+struct NamespaceView {
+    namespace: String,
+    is_expanded: bool,
+    sub_namespaces: Vec<NamespaceView>,
+    keys: Vec<String>,
+    /// How many of `keys` are listed, grown by `KEY_PAGE_SIZE` on request.
+    shown_keys: usize,
+    /// Keys in this namespace and all of its sub namespaces.
+    key_count: usize,
+}
+
+impl NamespaceView {
+    fn new(redis_namespace: RedisNamespace, descending: bool) -> Self {
+        let mut keys = redis_namespace.keys;
+        sort_keys(&mut keys, descending);
+
+        let sub_namespaces = create_namespace_views(redis_namespace.sub_namespaces, descending);
+        let key_count = keys.len() + sub_namespaces.iter().map(|ns| ns.key_count).sum::<usize>();
+
+        NamespaceView {
+            namespace: redis_namespace.name,
+            is_expanded: false,
+            sub_namespaces,
+            keys,
+            shown_keys: KEY_PAGE_SIZE,
+            key_count,
+        }
+    }
+}
+
+impl NamespaceView {
+    fn empty(name: &str) -> Self {
+        NamespaceView {
+            namespace: name.into(),
+            is_expanded: false,
+            sub_namespaces: Vec::<NamespaceView>::new(),
+            keys: Vec::<String>::new(),
+            shown_keys: KEY_PAGE_SIZE,
+            key_count: 0,
+        }
+    }
+}
+
+fn sort_namespace_views(views: &mut [NamespaceView], descending: bool) {
+    views.sort_by(|a, b| compare_names(&a.namespace, &b.namespace, descending));
+    for view in views.iter_mut() {
+        sort_keys(&mut view.keys, descending);
+        sort_namespace_views(&mut view.sub_namespaces, descending);
+    }
+}
+
+fn find_namespace_view<'a>(
+    views: &'a mut [NamespaceView],
+    indices: &[usize],
+) -> Option<&'a mut NamespaceView> {
+    let (first, rest) = indices.split_first()?;
+    let view = views.get_mut(*first)?;
+    if rest.is_empty() {
+        Some(view)
+    } else {
+        find_namespace_view(&mut view.sub_namespaces, rest)
+    }
+}
+
+/// One line of the key list. Rows are collected only for the visible window
+/// and own their text so the row widgets can borrow the shared button states.
+enum KeyTreeRow {
+    Namespace {
+        indices: Vec<usize>,
+        name: String,
+        key_count: usize,
+        is_expanded: bool,
+    },
+    Key {
+        depth: usize,
+        key: String,
+    },
+    MoreKeys {
+        indices: Vec<usize>,
+        remaining: usize,
+    },
+}
+
+fn count_tree_rows(views: &[NamespaceView]) -> usize {
+    views
+        .iter()
+        .map(|view| {
+            let mut rows = 1;
+            if view.is_expanded {
+                rows += count_tree_rows(&view.sub_namespaces);
+                rows += view.keys.len().min(view.shown_keys);
+                if view.keys.len() > view.shown_keys {
+                    rows += 1;
+                }
+            }
+            rows
+        })
+        .sum()
+}
+
+/// Collects up to `limit` rows of the expanded tree after skipping the first
+/// `skip` rows. Collapsed and skipped subtrees are never walked key by key.
+fn collect_tree_rows(
+    views: &[NamespaceView],
+    indices: &[usize],
+    skip: &mut usize,
+    limit: usize,
+    rows: &mut Vec<KeyTreeRow>,
+) {
+    for (i, view) in views.iter().enumerate() {
+        if rows.len() >= limit {
+            return;
+        }
+        let subtree_rows = count_tree_rows(std::slice::from_ref(view));
+        if *skip >= subtree_rows {
+            *skip -= subtree_rows;
+            continue;
+        }
+
+        let mut view_indices = indices.to_vec();
+        view_indices.push(i);
+        if *skip > 0 {
+            *skip -= 1;
+        } else {
+            rows.push(KeyTreeRow::Namespace {
+                indices: view_indices.clone(),
+                name: view.namespace.clone(),
+                key_count: view.key_count,
+                is_expanded: view.is_expanded,
+            });
+        }
+        if !view.is_expanded {
+            continue;
+        }
+
+        collect_tree_rows(&view.sub_namespaces, &view_indices, skip, limit, rows);
+
+        let shown = view.keys.len().min(view.shown_keys);
+        let start = (*skip).min(shown);
+        *skip -= start;
+        for key in view.keys[start..shown].iter() {
+            if rows.len() >= limit {
+                return;
+            }
+            rows.push(KeyTreeRow::Key {
+                depth: view_indices.len(),
+                key: key.clone(),
+            });
+        }
+
+        if view.keys.len() > shown {
+            if *skip > 0 {
+                *skip -= 1;
+            } else if rows.len() < limit {
+                rows.push(KeyTreeRow::MoreKeys {
+                    indices: view_indices,
+                    remaining: view.keys.len() - shown,
+                });
+            }
+        }
+    }
+}
+
+/// Namespace names leading to the view that lists `key`, following the
+/// layout of `convert_keys_to_namespaces`.
+fn namespace_path<'a>(key: &'a str, delimiters: &str) -> Vec<&'a str> {
+    let parts = split_key(key, delimiters);
+    if parts.len() > 1 {
+        parts
+    } else {
+        vec![""]
+    }
+}
+
+fn add_key_to_views(
+    views: &mut Vec<NamespaceView>,
+    path: &[&str],
+    key: &str,
+    descending: bool,
+) -> bool {
+    let index =
+        match views.binary_search_by(|view| compare_names(&view.namespace, path[0], descending)) {
+            Ok(index) => index,
+            Err(index) => {
+                views.insert(index, NamespaceView::empty(path[0]));
+                index
+            }
+        };
+    let view = &mut views[index];
+
+    let added = if path.len() == 1 {
+        insert_sorted_key(&mut view.keys, key, descending)
+    } else {
+        add_key_to_views(&mut view.sub_namespaces, &path[1..], key, descending)
+    };
+    if added {
+        view.key_count += 1;
+    }
+    added
+}
+
+fn remove_key_from_views(
+    views: &mut Vec<NamespaceView>,
+    path: &[&str],
+    key: &str,
+    descending: bool,
+) -> bool {
+    let index =
+        match views.binary_search_by(|view| compare_names(&view.namespace, path[0], descending)) {
+            Ok(index) => index,
+            Err(_) => return false,
+        };
+    let view = &mut views[index];
+
+    let removed = if path.len() == 1 {
+        remove_sorted_key(&mut view.keys, key, descending)
+    } else {
+        remove_key_from_views(&mut view.sub_namespaces, &path[1..], key, descending)
+    };
+    if removed {
+        view.key_count -= 1;
+    }
+
+    if view.keys.is_empty() && view.sub_namespaces.is_empty() {
+        views.remove(index);
+    }
+    removed
+}
+
+impl ServerTab {
+    fn new(name: String, source: KeySource, keys: Vec<String>, delimiters: String) -> Self {
+        let keys: HashSet<String> = keys.into_iter().collect();
+        let namespaces_view =
+            create_namespace_views(convert_keys_to_namespaces(&keys, &delimiters), false);
+
+        let mut server_tab = ServerTab {
+            name,
+            source,
+            keys,
+            keys_scrollbar_state: KeysScrollbarState::default(),
+            key_metadata: HashMap::<String, KeyMetadata>::new(),
+            key_selection_state: KeySelectionState::new(),
+            editor_state: EditorState::Empty,
+            key_grouping_state: KeyGroupingState::new(delimiters),
+            keyspace_state: KeyspaceState::new(),
+            health_state: HealthState::new(),
+            status: String::new(),
+            scanned_keys: None,
+            key_scan_generation: 0,
+            live_keys_state: LiveKeysState::new(),
+            pattern_delete_state: PatternDeleteState::new(),
+            keys_export_state: KeysExportState::new(),
+            keys_import_state: KeysImportState::new(),
+            keys_copy_state: KeysCopyState::new(),
+            namespaces_view,
+            panel: TabPanel::Keys,
+            info_state: InfoState::new(),
+            metrics_state: MetricsState::new(),
+            slowlog_state: SlowlogState::new(),
+            clients_state: ClientsState::new(),
+            monitor_state: MonitorState::new(),
+            pubsub_state: PubSubState::new(),
+            memory_state: MemoryState::new(),
+        };
+        server_tab.load_key_metadata();
+        if !server_tab.is_read_only() {
+            server_tab.reload_keys();
+            server_tab.load_keyspace();
+        }
+        server_tab
+    }
+
+    fn is_read_only(&self) -> bool {
+        matches!(self.source, KeySource::Rdb(_))
+    }
+
+    fn add_key(&mut self, key: String) {
+        if self.keys.contains(&key) {
+            return;
+        }
+        add_key_to_views(
+            &mut self.namespaces_view,
+            &namespace_path(&key, &self.key_grouping_state.delimiters),
+            &key,
+            self.key_grouping_state.sort_descending,
+        );
+        self.keys.insert(key);
+    }
+
+    fn remove_key(&mut self, key: &str) {
+        if !self.keys.remove(key) {
+            return;
+        }
+        remove_key_from_views(
+            &mut self.namespaces_view,
+            &namespace_path(key, &self.key_grouping_state.delimiters),
+            key,
+            self.key_grouping_state.sort_descending,
+        );
+        self.key_selection_state.selected.remove(key);
+    }
+
+    /// The SCAN pattern matching every key below the namespace row at
+    /// `indices`.
+    fn namespace_patterns_at(&self, indices: &[usize]) -> Vec<String> {
+        let mut path = Vec::<&str>::new();
+        let mut views = &self.namespaces_view[..];
+        for i in indices.iter() {
+            let view = &views[*i];
+            path.push(&view.namespace);
+            views = &view.sub_namespaces;
+        }
+        namespace_patterns(&path, &self.key_grouping_state.delimiters)
+    }
+
+    /// Collects the keys matching any of `patterns`. SCAN may return a key
+    /// more than once, so the result is deduplicated.
+    fn scan_patterns(&mut self, patterns: &[String]) -> redis::RedisResult<Vec<String>> {
+        let mut keys = Vec::new();
+        for pattern in patterns.iter() {
+            keys.extend(scan_keys(self.source.server_connection()?, pattern)?);
+        }
+        keys.sort_unstable();
+        keys.dedup();
+        Ok(keys)
+    }
+
+    /// Writes `keys` to the export path in the chosen format.
+    fn export_keys(&mut self, keys: &[String]) {
+        let keys_export_state = &mut self.keys_export_state;
+        let path = &keys_export_state.path_value;
+        let format = keys_export_state.format;
+        let write = |snapshot| match format {
+            FileFormat::Json => KeysSnapshot::write(snapshot, path),
+            FileFormat::Commands => write_script(snapshot, path),
+        };
+        keys_export_state.status = match self
+            .source
+            .server_connection()
+            .and_then(|con| dump_keys(con, keys))
+        {
+            Ok((snapshot, skipped)) => match write(&snapshot) {
+                Ok(()) if skipped.is_empty() => {
+                    format!("Exported {} keys to {}", snapshot.keys.len(), path)
+                }
+                Ok(()) => format!(
+                    "Exported {} keys to {}, skipped {} of unsupported types",
+                    snapshot.keys.len(),
+                    path,
+                    skipped.len()
+                ),
+                Err(e) => format!("Failed to export: {}", e),
+            },
+            Err(e) => format!("Failed to export: {}", e),
+        };
+    }
+
+    /// Lists the keys again. A server is scanned in the background by a
+    /// `KeyScanStream`, whose result arrives through `replace_keys`.
+    fn reload_keys(&mut self) {
+        match &self.source {
+            KeySource::Server(..) => {
+                self.key_scan_generation += 1;
+                self.scanned_keys = Some(Vec::new());
+            }
+            KeySource::Rdb(database) => {
+                let keys = database.keys(self.key_grouping_state.type_filter.type_name());
+                self.replace_keys(keys);
+            }
+        }
+    }
+
+    fn replace_keys(&mut self, keys: Vec<String>) {
+        self.key_metadata.clear();
+        self.sync_keys(keys);
+        self.load_key_metadata();
+    }
+
+    /// Selects every listed key between the selection anchor and `key`, in
+    /// the order the key list shows them.
+    fn select_key_range(&mut self, key: String) {
+        let anchor = match self.key_selection_state.anchor.clone() {
+            Some(anchor) => anchor,
+            None => {
+                self.key_selection_state.toggle(key, true);
+                return;
+            }
+        };
+
+        let mut first_row = 0;
+        let (rows, _) = collect_key_rows(
+            &mut first_row,
+            usize::MAX,
+            self.key_grouping_state.delimiters.is_empty(),
+            &self.namespaces_view,
+        );
+        let ordered: Vec<String> = rows
+            .into_iter()
+            .filter_map(|row| match row {
+                KeyTreeRow::Key { key, .. } => Some(key),
+                _ => None,
+            })
+            .collect();
+
+        let selection = &mut self.key_selection_state;
+        match (
+            ordered.iter().position(|k| *k == anchor),
+            ordered.iter().position(|k| *k == key),
+        ) {
+            (Some(start), Some(end)) => {
+                let (start, end) = if start <= end {
+                    (start, end)
+                } else {
+                    (end, start)
+                };
+                selection
+                    .selected
+                    .extend(ordered[start..=end].iter().cloned());
+                selection.anchor = Some(key);
+            }
+            _ => selection.toggle(key, true),
+        }
+    }
+
+    /// Fetches type and expiry for the keys in the visible window that have
+    /// not been looked up yet.
+    fn load_key_metadata(&mut self) {
+        let (rows, _) = collect_key_rows(
+            &mut self.keys_scrollbar_state.first_row,
+            KEY_TREE_WINDOW,
+            self.key_grouping_state.delimiters.is_empty(),
+            &self.namespaces_view,
+        );
+        let missing: Vec<String> = rows
+            .into_iter()
+            .filter_map(|row| match row {
+                KeyTreeRow::Key { key, .. } if !self.key_metadata.contains_key(&key) => Some(key),
+                _ => None,
+            })
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        let metadata = match &mut self.source {
+            KeySource::Server(con, _) => get_key_metadata(con, &missing),
+            KeySource::Rdb(database) => Ok(database.metadata(&missing)),
+        };
+        let metadata = match metadata {
+            Ok(metadata) => metadata,
+            Err(e) => return self.command_failed("read key types", e),
+        };
+        self.key_metadata.extend(missing.into_iter().zip(metadata));
+    }
+
+    /// Rebuilds the tree after the delimiters changed. Namespaces no longer
+    /// line up with the old ones, so expansion starts over.
+    fn regroup_keys(&mut self) {
+        self.namespaces_view = create_namespace_views(
+            convert_keys_to_namespaces(&self.keys, &self.key_grouping_state.delimiters),
+            self.key_grouping_state.sort_descending,
+        );
+    }
+
+    /// Flips between ascending and descending order without rebuilding, so
+    /// expanded namespaces stay open.
+    fn toggle_key_sort(&mut self) {
+        let descending = !self.key_grouping_state.sort_descending;
+        self.key_grouping_state.sort_descending = descending;
+        sort_namespace_views(&mut self.namespaces_view, descending);
+    }
+
+    /// Brings the key list and tree in line with `keys`, touching only what
+    /// changed so expanded namespaces, scroll position and the open key
+    /// survive a refresh.
+    fn sync_keys(&mut self, keys: Vec<String>) {
+        let fresh: HashSet<&str> = keys.iter().map(String::as_str).collect();
+        let stale: Vec<String> = self
+            .keys
+            .iter()
+            .filter(|key| !fresh.contains(key.as_str()))
+            .cloned()
+            .collect();
+        let delimiters = &self.key_grouping_state.delimiters;
+        for key in stale.iter() {
+            remove_key_from_views(
+                &mut self.namespaces_view,
+                &namespace_path(key, delimiters),
+                key,
+                self.key_grouping_state.sort_descending,
+            );
+        }
+        self.keys.retain(|key| fresh.contains(key.as_str()));
+        self.key_selection_state
+            .selected
+            .retain(|key| fresh.contains(key.as_str()));
+
+        if matches!(self.editor_state.key(), Some(key) if !fresh.contains(key)) {
+            self.editor_state = EditorState::Empty;
+        }
+
+        for key in keys {
+            self.add_key(key);
+        }
+    }
+
+    /// Applies a `__keyevent@<db>__` notification to the key list, the tree
+    /// and the editor when it shows the affected key.
+    fn apply_key_event(&mut self, event: &str, key: String) {
+        let is_removal = matches!(
+            event,
+            "del" | "expired" | "evicted" | "rename_from" | "move_from"
+        );
+        let is_open = self.editor_state.key() == Some(key.as_str());
+
+        self.key_metadata.remove(&key);
+        if is_removal {
+            self.remove_key(&key);
+            if is_open {
+                self.editor_state = EditorState::Empty;
+            }
+            return;
+        }
+
+        if let Some(type_name) = self.key_grouping_state.type_filter.type_name() {
+            match self
+                .source
+                .server_connection()
+                .and_then(|con| get_key_metadata(con, std::slice::from_ref(&key)))
+            {
+                Ok(metadata) => match metadata.first() {
+                    Some(metadata) if metadata.key_type == type_name => {}
+                    // gone again, or not of the type the tree is filtered to
+                    _ => {
+                        self.remove_key(&key);
+                        return;
+                    }
+                },
+                Err(e) => {
+                    self.live_keys_state.status =
+                        format!("Failed to check the type of {}: {}", key, e);
+                    return;
+                }
+            }
+        }
+
+        if is_open {
+            match &self.editor_state {
+                // reloading would throw away what the user typed
+                EditorState::Edit(edit_state) if edit_state.is_modified() => {
+                    self.status = format!("{} changed on server — reload?", key);
+                }
+                _ => self.open_key(key.clone()),
+            }
+        }
+        self.add_key(key);
+    }
+
+    fn load_keyspace(&mut self) {
+        match self.source.server_connection().and_then(get_keyspace) {
+            Ok(databases) => self.keyspace_state.databases = databases,
+            Err(e) => self.keyspace_state.status = format!("Failed to read keyspace: {}", e),
+        }
+    }
+
+    /// PINGs the server, or tries to reconnect once the backoff has passed.
+    /// The new connection selects `settings.db`, so a db picked with
+    /// `switch_db` survives the reconnect.
+    fn check_health(&mut self) {
+        if self.health_state.is_connected() {
+            if let Err(e) = self.source.server_connection().and_then(ping) {
+                self.health_state.connection_lost(e.to_string());
+            }
+            return;
+        }
+        if matches!(self.health_state.retry_at, Some(retry_at) if retry_at > Instant::now()) {
+            return;
+        }
+
+        let settings = match self.source.settings() {
+            Some(settings) => settings.clone(),
+            None => return,
+        };
+        match connect_redis(&settings) {
+            Ok(con) => {
+                self.source = KeySource::Server(con, settings);
+                self.health_state = HealthState::new();
+                self.status.clear();
+                // the new connection has a CLIENT ID of its own
+                self.clients_state.own_id = None;
+                self.reload_keys();
+                self.load_keyspace();
+            }
+            Err(e) => self.health_state.reconnect_failed(e.to_string()),
+        }
+    }
+
+    /// Points the tab's connection at another database with SELECT. Anything
+    /// shown for the old database is dropped and the key tree reloaded.
+    fn switch_db(&mut self, db: i64) {
+        if self.pattern_delete_state.running_patterns.is_some() {
+            self.keyspace_state.status = "Wait for the pattern delete to finish".into();
+            return;
+        }
+        if let Err(e) = self
+            .source
+            .server_connection()
+            .and_then(|con| select_db(con, db))
+        {
+            self.keyspace_state.status = format!("Failed to select db {}: {}", db, e);
+            return;
+        }
+
+        // streams reconnect with the new settings
+        if let KeySource::Server(_, settings) = &mut self.source {
+            settings.db = db;
+        }
+        self.editor_state = EditorState::Empty;
+        self.key_selection_state = KeySelectionState::new();
+        self.pattern_delete_state.preview = None;
+        self.memory_state = MemoryState::new();
+        self.keys_scrollbar_state.scroll_to_top();
+        self.reload_keys();
+        self.keyspace_state.status.clear();
+        self.keyspace_state.db_value.clear();
+        self.load_keyspace();
+    }
+
+    /// Shows `key` in the editor, editable when it holds a string on a
+    /// server.
+    fn open_key(&mut self, key: String) {
+        let value = match &mut self.source {
+            KeySource::Server(con, _) => get_redis_value(con, &key),
+            KeySource::Rdb(database) => Ok(database.value(&key)),
+        };
+        let value = match value {
+            Ok(value) => value,
+            Err(e) => return self.command_failed(&format!("read {}", key), e),
+        };
+        self.editor_state = match value {
+            RedisValue::String(value) if !self.is_read_only() => {
+                EditorState::Edit(ValueEditState {
+                    key,
+                    saved_value: value.clone(),
+                    value,
+                    ..default()
+                })
+            }
+            value => EditorState::Inspect(ValueInspectState::new(key, value)),
+        };
+    }
+
+    /// Shows a failed command in the tab's status line. An IO error means
+    /// the connection itself broke, so the tab is marked disconnected and
+    /// `check_health` reconnects it.
+    fn command_failed(&mut self, action: &str, e: redis::RedisError) {
+        if e.is_io_error() {
+            self.health_state.connection_lost(e.to_string());
+        }
+        self.status = format!("Failed to {}: {}", action, e);
+    }
+
+    fn refresh_info(&mut self) {
+        if let Some(con) = self.source.connection() {
+            if let Err(e) = self.info_state.refresh(con) {
+                self.command_failed("read INFO", e);
+            }
+        }
+    }
+
+    fn sample_metrics(&mut self) {
+        if let Some(con) = self.source.connection() {
+            if let Err(e) = self.metrics_state.sample(con) {
+                self.command_failed("sample metrics", e);
+            }
+        }
+    }
+
+    fn fetch_slowlog(&mut self) {
+        if let Some(con) = self.source.connection() {
+            if let Err(e) = self.slowlog_state.fetch(con) {
+                self.command_failed("read the slowlog", e);
+            }
+        }
+    }
+
+    fn refresh_clients(&mut self) {
+        if let Some(con) = self.source.connection() {
+            if let Err(e) = self.clients_state.refresh(con) {
+                self.command_failed("list clients", e);
+            }
+        }
+    }
+}
+
+impl RedisViewer {
+    /// Copies `keys` from the current tab to the target picked in its copy
+    /// controls, then lists them in the target tab.
+    fn copy_keys_from_current_tab(&mut self, keys: &[String]) {
+        let current_index = self
+            .current_server_tab_index
+            .expect("failed to find current server tab index");
+        let current_server_tab = self
+            .server_tabs
+            .get_mut(current_index)
+            .expect("failed to find current server tab in arena");
+        let keys_copy_state = &current_server_tab.keys_copy_state;
+        let replace = keys_copy_state.replace;
+        let target_db = keys_copy_state.db_value.trim().parse::<i64>();
+
+        let report = match keys_copy_state.target.clone() {
+            Some(CopyTarget::Tab(target_index, name)) => {
+                match self.server_tabs.get2_mut(current_index, target_index) {
+                    (Some(source_tab), Some(target_tab)) => {
+                        match (
+                            source_tab.source.connection(),
+                            target_tab.source.connection(),
+                        ) {
+                            (Some(source), Some(target)) => {
+                                let report = copy_keys(source, target, keys, replace);
+                                target_tab.reload_keys();
+                                Ok(report)
+                            }
+                            _ => Err("Keys can only be copied between server tabs".into()),
+                        }
+                    }
+                    _ => Err(format!("Tab {} is no longer open", name)),
+                }
+            }
+            Some(CopyTarget::Database) => match target_db {
+                Ok(db) => match &mut current_server_tab.source {
+                    KeySource::Server(source, settings) => {
+                        let settings = ConnectionSettings {
+                            db,
+                            ..settings.clone()
+                        };
+                        match connect_redis(&settings) {
+                            Ok(mut target) => Ok(copy_keys(source, &mut target, keys, replace)),
+                            Err(e) => Err(format!("Failed to connect to db {}: {}", db, e)),
+                        }
+                    }
+                    KeySource::Rdb(_) => Err("Keys can only be copied between server tabs".into()),
+                },
+                Err(_) => Err("Enter the number of the target db".into()),
+            },
+            None => return,
+        };
+
+        let keys_copy_state = &mut self
+            .server_tabs
+            .get_mut(current_index)
+            .expect("failed to find current server tab in arena")
+            .keys_copy_state;
+        match report {
+            Ok(report) => {
+                let copied = report.iter().filter(|(_, result)| result.is_ok()).count();
+                keys_copy_state.status = format!("Copied {} of {} keys", copied, report.len());
+                keys_copy_state.report = report;
+                keys_copy_state.report_scrollbar_state = scrollable::State::default();
+            }
+            Err(e) => keys_copy_state.status = e,
+        }
+    }
+
+    fn follow_key_list_scroll(&mut self) {
+        let current_server_tab = self
+            .current_server_tab_index
+            .and_then(|index| self.server_tabs.get_mut(index));
+        if let Some(current_server_tab) = current_server_tab {
+            current_server_tab.keys_scrollbar_state.follow_scroll();
+            current_server_tab.load_key_metadata();
+        }
+    }
+
+    fn refresh_keys(&mut self) {
+        let server_tab = self
+            .server_tabs
+            .get_mut(
+                self.current_server_tab_index
+                    .expect("failed to find current server tab index"),
+            )
+            .expect("failed to find current server tab in arena");
+        // a stale connection is replaced by the health check, not here
+        if !server_tab.health_state.is_connected() {
+            return;
+        }
+        server_tab.reload_keys();
+        server_tab.load_keyspace();
+    }
+}
+
+fn create_namespace_views(
+    namespaces: HashMap<String, RedisNamespace>,
+    descending: bool,
+) -> Vec<NamespaceView> {
+    let mut namespaces_view: Vec<NamespaceView> = namespaces
+        .into_values()
+        .map(|ns| NamespaceView::new(ns, descending))
+        .collect();
+    namespaces_view.sort_by(|a, b| compare_names(&a.namespace, &b.namespace, descending));
+
+    namespaces_view
+}
+
+fn create_key_tree_row<'a>(
+    row: KeyTreeRow,
+    state: &'a mut button::State,
+    namespace_buttons: &'a mut NamespaceRowButtons,
+    key_metadata: &HashMap<String, KeyMetadata>,
+    selected: &HashSet<String>,
+    read_only: bool,
+) -> Row<'a, Message> {
+    let (depth, content): (usize, Element<'a, Message>) = match row {
+        KeyTreeRow::Namespace {
+            indices,
+            name,
+            key_count,
+            is_expanded,
+        } => {
+            let expander_text = if is_expanded {
+                Text::new("^")
+            } else {
+                Text::new(">")
+            };
+            let delete_button =
+                Button::new(&mut namespace_buttons.delete, Text::new("Delete").size(14)).padding(2);
+            let export_button =
+                Button::new(&mut namespace_buttons.export, Text::new("Export").size(14)).padding(2);
+            let copy_button =
+                Button::new(&mut namespace_buttons.copy, Text::new("Copy").size(14)).padding(2);
+            // keys without any delimiter sit in a nameless root namespace
+            // that no pattern can single out
+            let (delete_button, export_button, copy_button) = if name.is_empty() {
+                (delete_button, export_button, copy_button)
+            } else {
+                (
+                    delete_button.on_press(Message::DeleteNamespace(indices.clone())),
+                    export_button.on_press(Message::ExportNamespace(indices.clone())),
+                    copy_button.on_press(Message::CopyNamespace(indices.clone())),
+                )
+            };
+            let depth = indices.len() - 1;
+            let namespace_row = Row::new()
+                .spacing(5)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(state, expander_text)
+                        .padding(5)
+                        .on_press(Message::NamespaceExpandToggle(indices)),
+                )
+                .push(Text::new(format!("{} ({})", name, key_count)));
+            let namespace_row = if read_only {
+                namespace_row
+            } else {
+                namespace_row
+                    .push(delete_button)
+                    .push(export_button)
+                    .push(copy_button)
+            };
+            (depth, namespace_row.into())
+        }
+        KeyTreeRow::Key { depth, key } => {
+            let (badge, expiry) = match key_metadata.get(&key) {
+                Some(metadata) => (
+                    type_badge(&metadata.key_type).to_string(),
+                    if metadata.ttl_millis >= 0 {
+                        format!(
+                            "expires in {}",
+                            format_duration(metadata.ttl_millis as u64 / 1000)
+                        )
+                    } else {
+                        String::new()
+                    },
+                ),
+                None => (String::new(), String::new()),
+            };
+            let key_row = Row::new().spacing(5).align_items(Align::Center);
+            let key_row = if read_only {
+                key_row
+            } else {
+                let checked_key = key.clone();
+                key_row.push(Checkbox::new(
+                    selected.contains(&key),
+                    "",
+                    move |is_selected| Message::KeyChecked(checked_key.clone(), is_selected),
+                ))
+            };
+            (
+                depth,
+                key_row
+                    .push(
+                        Button::new(state, Text::new(key.clone()))
+                            .padding(5)
+                            .on_press(Message::KeySelected(key)),
+                    )
+                    .push(Text::new(badge).size(14))
+                    .push(Text::new(expiry).size(14))
+                    .into(),
+            )
+        }
+        KeyTreeRow::MoreKeys { indices, remaining } => (
+            indices.len(),
+            Button::new(
+                state,
+                Text::new(format!(
+                    "Show next {} ({} more)",
+                    remaining.min(KEY_PAGE_SIZE),
+                    remaining
+                )),
+            )
+            .padding(5)
+            .on_press(Message::ShowMoreKeys(indices))
+            .into(),
+        ),
+    };
+
+    Row::new()
+        .height(Length::Units(KEY_ROW_HEIGHT))
+        .align_items(Align::Center)
+        .push(Space::with_width(Length::Units(depth as u16 * 10)))
+        .push(content)
+}
+
+/// Clamps `first_row` to the rows that exist and collects up to `limit` rows
+/// starting there. Also returns the total number of rows. A flat list shows
+/// the keys of the nameless root namespace without the namespace row.
+fn collect_key_rows(
+    first_row: &mut usize,
+    limit: usize,
+    is_flat: bool,
+    namespaces_view: &[NamespaceView],
+) -> (Vec<KeyTreeRow>, usize) {
+    let flat_keys = || namespaces_view.iter().flat_map(|view| view.keys.iter());
+    let total_rows = if is_flat {
+        namespaces_view.iter().map(|view| view.keys.len()).sum()
+    } else {
+        count_tree_rows(namespaces_view)
+    };
+    *first_row = (*first_row).min(total_rows.saturating_sub(limit.min(KEY_TREE_WINDOW)));
+
+    let rows = if is_flat {
+        flat_keys()
+            .skip(*first_row)
+            .take(limit)
+            .map(|key| KeyTreeRow::Key {
+                depth: 0,
+                key: key.clone(),
+            })
+            .collect()
+    } else {
+        let mut rows = Vec::<KeyTreeRow>::new();
+        let mut skip = *first_row;
+        collect_tree_rows(namespaces_view, &[], &mut skip, limit, &mut rows);
+        rows
+    };
+    (rows, total_rows)
+}
+
+fn create_delete_keys_confirmation(key_selection_state: &mut KeySelectionState) -> Column<Message> {
+    let mut preview: Vec<&String> = key_selection_state.selected.iter().collect();
+    preview.sort_by(|a, b| natural_cmp(a, b));
+    let remaining = preview.len().saturating_sub(DELETE_PREVIEW_KEYS);
+
+    let keys = preview.into_iter().take(DELETE_PREVIEW_KEYS).fold(
+        Scrollable::new(&mut key_selection_state.preview_scrollbar_state)
+            .spacing(2)
+            .width(Length::Fill)
+            .height(Length::Fill),
+        |scrollable, key| scrollable.push(Text::new(key.clone())),
+    );
+    let keys = if remaining > 0 {
+        keys.push(Text::new(format!("and {} more", remaining)))
+    } else {
+        keys
+    };
+
+    Column::new()
+        .padding(10)
+        .spacing(10)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .push(Text::new(format!(
+            "Delete {} keys? This cannot be undone.",
+            key_selection_state.selected.len()
+        )))
+        .push(keys)
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(
+                    Button::new(
+                        &mut key_selection_state.confirm_button_state,
+                        Text::new("Delete"),
+                    )
+                    .on_press(Message::ConfirmDeleteSelectedKeys),
+                )
+                .push(
+                    Button::new(
+                        &mut key_selection_state.cancel_button_state,
+                        Text::new("Cancel"),
+                    )
+                    .on_press(Message::CancelDeleteSelectedKeys),
+                ),
+        )
+}
+
+fn create_pattern_delete_view(pattern_delete_state: &mut PatternDeleteState) -> Column<Message> {
+    let is_running = pattern_delete_state.running_patterns.is_some();
+    let preview_matched = match &pattern_delete_state.preview {
+        Some((patterns, progress)) if *patterns == pattern_delete_state.patterns() => {
+            Some(progress.matched)
+        }
+        _ => None,
+    };
+
+    let mut controls = Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(
+            TextInput::new(
+                &mut pattern_delete_state.pattern_input_state,
+                "Key pattern, e.g. cache:*",
+                &pattern_delete_state.pattern_value,
+                Message::DeletePatternChanged,
+            )
+            .padding(5)
+            .width(Length::FillPortion(2)),
+        );
+    if is_running {
+        controls = controls.push(
+            Button::new(
+                &mut pattern_delete_state.cancel_button_state,
+                Text::new("Cancel"),
+            )
+            .on_press(Message::CancelPatternDelete),
+        );
+    } else {
+        let preview_button = Button::new(
+            &mut pattern_delete_state.preview_button_state,
+            Text::new("Preview"),
+        );
+        let preview_button = if pattern_delete_state.pattern_value.is_empty() {
+            preview_button
+        } else {
+            preview_button.on_press(Message::PreviewPatternDelete)
+        };
+        let delete_button = Button::new(
+            &mut pattern_delete_state.delete_button_state,
+            Text::new(match preview_matched {
+                Some(matched) => format!("Delete {} keys", matched),
+                None => "Delete".into(),
+            }),
+        );
+        let delete_button = match preview_matched {
+            Some(matched) if matched > 0 => delete_button.on_press(Message::RunPatternDelete),
+            _ => delete_button,
+        };
+        controls = controls.push(preview_button).push(delete_button);
+    }
+    controls = controls.push(
+        Text::new(&pattern_delete_state.status)
+            .size(14)
+            .width(Length::FillPortion(3)),
+    );
+
+    let samples = if preview_matched.is_some() || is_running {
+        let progress = &pattern_delete_state.progress;
+        let more = progress
+            .matched
+            .saturating_sub(progress.samples.len() as u64);
+        let samples = progress.samples.join(", ");
+        if more > 0 {
+            format!("{} and {} more", samples, more)
+        } else {
+            samples
+        }
+    } else {
+        String::new()
+    };
+
+    Column::new()
+        .push(controls)
+        .push(Row::new().padding(5).push(Text::new(samples).size(14)))
+}
+
+fn create_keys_export_controls(
+    keys_export_state: &mut KeysExportState,
+    selected_count: usize,
+    has_pattern: bool,
+) -> Row<Message> {
+    let selected_button = Button::new(
+        &mut keys_export_state.selected_button_state,
+        Text::new("Export selected"),
+    );
+    let selected_button = if selected_count > 0 {
+        selected_button.on_press(Message::ExportSelectedKeys)
+    } else {
+        selected_button
+    };
+    let pattern_button = Button::new(
+        &mut keys_export_state.pattern_button_state,
+        Text::new("Export pattern matches"),
+    );
+    let pattern_button = if has_pattern {
+        pattern_button.on_press(Message::ExportPatternKeys)
+    } else {
+        pattern_button
+    };
+
+    Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(PickList::new(
+            &mut keys_export_state.format_list_state,
+            &FileFormat::ALL[..],
+            Some(keys_export_state.format),
+            Message::ExportFormatChanged,
+        ))
+        .push(
+            TextInput::new(
+                &mut keys_export_state.path_input_state,
+                "Export file path",
+                &keys_export_state.path_value,
+                Message::ExportPathChanged,
+            )
+            .width(Length::Units(200))
+            .padding(5),
+        )
+        .push(selected_button)
+        .push(pattern_button)
+        .push(Text::new(&keys_export_state.status).size(14))
+}
+
+fn create_key_selection_controls(key_selection_state: &mut KeySelectionState) -> Row<Message> {
+    let count = key_selection_state.selected.len();
+    let delete_button = Button::new(
+        &mut key_selection_state.delete_button_state,
+        Text::new(format!("Delete {} selected", count)),
+    );
+    let clear_button = Button::new(
+        &mut key_selection_state.clear_button_state,
+        Text::new("Clear"),
+    );
+    let (delete_button, clear_button) = if count > 0 {
+        (
+            delete_button.on_press(Message::DeleteSelectedKeys),
+            clear_button.on_press(Message::ClearKeySelection),
+        )
+    } else {
+        (delete_button, clear_button)
+    };
+
+    Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(delete_button)
+        .push(clear_button)
+        .push(Text::new(&key_selection_state.status))
+}
+
+fn create_keys_view<'a>(
+    scrollbar_state: &'a mut KeysScrollbarState,
+    key_grouping_state: &'a mut KeyGroupingState,
+    key_selection_state: &'a mut KeySelectionState,
+    namespaces_view: &[NamespaceView],
+    key_metadata: &HashMap<String, KeyMetadata>,
+    read_only: bool,
+) -> Column<'a, Message> {
+    if key_selection_state.is_confirming_delete {
+        return Column::new()
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .push(create_key_grouping_controls(key_grouping_state))
+            .push(create_delete_keys_confirmation(key_selection_state));
+    }
+
+    let (rows, total_rows) = collect_key_rows(
+        &mut scrollbar_state.first_row,
+        KEY_TREE_WINDOW,
+        key_grouping_state.delimiters.is_empty(),
+        namespaces_view,
+    );
+    let first_row = scrollbar_state.first_row;
+    let rows_below = total_rows - first_row - rows.len();
+    scrollbar_state
+        .row_buttons
+        .resize_with(rows.len(), button::State::default);
+
+    scrollbar_state
+        .row_namespace_buttons
+        .resize_with(rows.len(), Default::default);
+
+    let keys = Scrollable::new(&mut scrollbar_state.state)
+        .padding(0)
+        .align_items(Align::Start)
+        .width(Length::Fill)
+        .height(Length::Fill);
+    let keys = push_row_spaces(keys, first_row);
+    let keys = rows
+        .into_iter()
+        .zip(
+            scrollbar_state
+                .row_buttons
+                .iter_mut()
+                .zip(scrollbar_state.row_namespace_buttons.iter_mut()),
+        )
+        .fold(keys, |scrollable, (row, (state, namespace_buttons))| {
+            scrollable.push(create_key_tree_row(
+                row,
+                state,
+                namespace_buttons,
+                key_metadata,
+                &key_selection_state.selected,
+                read_only,
+            ))
+        });
+    let keys = push_row_spaces(keys, rows_below);
+
+    let keys_view = Column::new()
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .push(create_key_grouping_controls(key_grouping_state))
+        .push(keys);
+    if read_only {
+        keys_view
+    } else {
+        keys_view.push(create_key_selection_controls(key_selection_state))
+    }
+}
+
+/// Stands in for `rows` key list rows that aren't built. A space is at most
+/// `u16::MAX` units high, so tall gaps take several.
+fn push_row_spaces(mut scrollable: Scrollable<Message>, rows: usize) -> Scrollable<Message> {
+    let mut height = rows.saturating_mul(KEY_ROW_HEIGHT as usize);
+    while height > 0 {
+        let part = height.min(u16::MAX as usize);
+        scrollable = scrollable.push(Space::with_height(Length::Units(part as u16)));
+        height -= part;
+    }
+    scrollable
+}
+
+/// Pattern delete and JSON export, which act on more than the listed keys.
+fn create_key_tools_view<'a>(
+    pattern_delete_state: &'a mut PatternDeleteState,
+    keys_export_state: &'a mut KeysExportState,
+    keys_import_state: &'a mut KeysImportState,
+    keys_copy_state: &'a mut KeysCopyState,
+    copy_targets: Vec<CopyTarget>,
+    selected_count: usize,
+) -> Column<'a, Message> {
+    let has_pattern = !pattern_delete_state.pattern_value.is_empty();
+    Column::new()
+        .push(create_pattern_delete_view(pattern_delete_state))
+        .push(create_keys_export_controls(
+            keys_export_state,
+            selected_count,
+            has_pattern,
+        ))
+        .push(create_keys_import_view(keys_import_state))
+        .push(create_keys_copy_view(
+            keys_copy_state,
+            copy_targets,
+            selected_count,
+        ))
+}
+
+fn create_keys_copy_view(
+    keys_copy_state: &mut KeysCopyState,
+    copy_targets: Vec<CopyTarget>,
+    selected_count: usize,
+) -> Column<Message> {
+    let copy_button = Button::new(
+        &mut keys_copy_state.copy_button_state,
+        Text::new("Copy selected"),
+    );
+    let copy_button = if selected_count > 0 && keys_copy_state.target.is_some() {
+        copy_button.on_press(Message::CopySelectedKeys)
+    } else {
+        copy_button
+    };
+
+    let mut controls = Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(PickList::new(
+            &mut keys_copy_state.target_list_state,
+            copy_targets,
+            keys_copy_state.target.clone(),
+            Message::CopyTargetChanged,
+        ));
+    if keys_copy_state.target == Some(CopyTarget::Database) {
+        controls = controls.push(
+            TextInput::new(
+                &mut keys_copy_state.db_input_state,
+                "db",
+                &keys_copy_state.db_value,
+                Message::CopyDbChanged,
+            )
+            .width(Length::Units(50))
+            .padding(5),
+        );
+    }
+    controls = controls
+        .push(Checkbox::new(
+            keys_copy_state.replace,
+            "Replace existing",
+            Message::CopyReplaceToggled,
+        ))
+        .push(copy_button)
+        .push(Text::new(&keys_copy_state.status).size(14));
+
+    let mut copy_view = Column::new().push(controls);
+    if !keys_copy_state.report.is_empty() {
+        copy_view = copy_view.push(
+            keys_copy_state.report.iter().fold(
+                Scrollable::new(&mut keys_copy_state.report_scrollbar_state)
+                    .padding(5)
+                    .width(Length::Fill)
+                    .height(Length::Units(COPY_REPORT_HEIGHT)),
+                |scrollable, (key, result)| {
+                    scrollable.push(
+                        Text::new(match result {
+                            Ok(()) => format!("{}: copied", key),
+                            Err(e) => format!("{}: {}", key, e),
+                        })
+                        .size(14),
+                    )
+                },
+            ),
+        );
+    }
+    copy_view
+}
+
+fn create_keys_import_view(keys_import_state: &mut KeysImportState) -> Column<Message> {
+    let import_button = Button::new(
+        &mut keys_import_state.import_button_state,
+        Text::new("Import"),
+    );
+    let import_button =
+        if keys_import_state.snapshot.is_some() || keys_import_state.commands.is_some() {
+            import_button.on_press(Message::RunImport)
+        } else {
+            import_button
+        };
+
+    let is_json = keys_import_state.format == FileFormat::Json;
+    let mut controls = Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(PickList::new(
+            &mut keys_import_state.format_list_state,
+            &FileFormat::ALL[..],
+            Some(keys_import_state.format),
+            Message::ImportFormatChanged,
+        ))
+        .push(
+            TextInput::new(
+                &mut keys_import_state.path_input_state,
+                "Import file path",
+                &keys_import_state.path_value,
+                Message::ImportPathChanged,
+            )
+            .width(Length::Units(200))
+            .padding(5),
+        );
+    // scripts say themselves what happens to existing keys
+    if is_json {
+        controls = controls.push(PickList::new(
+            &mut keys_import_state.policy_list_state,
+            &ConflictPolicy::ALL[..],
+            Some(keys_import_state.policy),
+            Message::ImportPolicyChanged,
+        ));
+    }
+    if is_json && keys_import_state.policy == ConflictPolicy::Rename {
+        controls = controls.push(
+            TextInput::new(
+                &mut keys_import_state.suffix_input_state,
+                "Suffix",
+                &keys_import_state.suffix_value,
+                Message::ImportSuffixChanged,
+            )
+            .width(Length::Units(100))
+            .padding(5),
+        );
+    }
+    controls = controls
+        .push(
+            Button::new(
+                &mut keys_import_state.preview_button_state,
+                Text::new("Preview import"),
+            )
+            .on_press(Message::PreviewImport),
+        )
+        .push(import_button)
+        .push(Text::new(&keys_import_state.status).size(14));
+
+    let mut import_view = Column::new().push(controls);
+    if let Some((_, preview)) = &keys_import_state.snapshot {
+        let types = preview
+            .types
+            .iter()
+            .map(|(key_type, count)| format!("{} {}", count, key_type))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let existing = match keys_import_state.policy {
+            ConflictPolicy::Skip => format!("{} existing keys will be skipped", preview.existing),
+            ConflictPolicy::Overwrite => {
+                format!("{} existing keys will be overwritten", preview.existing)
+            }
+            ConflictPolicy::Rename => format!(
+                "{} existing keys will be written with the suffix {}",
+                preview.existing, keys_import_state.suffix_value
+            ),
+        };
+        import_view = import_view.push(
+            Row::new().padding(5).push(
+                Text::new(format!(
+                    "{} keys ({}), {} with a TTL. {}.",
+                    preview.total, types, preview.with_ttl, existing
+                ))
+                .size(14),
+            ),
+        );
+    }
+    let failures: Vec<String> = match (
+        &keys_import_state.report,
+        &keys_import_state.commands_report,
+    ) {
+        (Some(report), _) => report
+            .failures
+            .iter()
+            .map(|(key, e)| format!("{}: {}", key, e))
+            .collect(),
+        (None, Some(report)) => report.failures.clone(),
+        (None, None) => Vec::<String>::new(),
+    };
+    let remaining = failures.len().saturating_sub(IMPORT_REPORT_FAILURES);
+    let failures = failures
+        .into_iter()
+        .take(IMPORT_REPORT_FAILURES)
+        .fold(Column::new().padding(5), |column, failure| {
+            column.push(Text::new(failure).size(14))
+        });
+    import_view.push(if remaining > 0 {
+        failures.push(Text::new(format!("and {} more failures", remaining)).size(14))
+    } else {
+        failures
+    })
+}
+
+fn create_info_view(info_state: &mut InfoState) -> Column<Message> {
+    let controls = Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(
+            Button::new(&mut info_state.refresh_button_state, Text::new("Refresh"))
+                .on_press(Message::RefreshInfo),
+        )
+        .push(PickList::new(
+            &mut info_state.interval_list_state,
+            &RefreshInterval::ALL[..],
+            Some(info_state.interval),
+            Message::InfoIntervalChanged,
+        ));
+
+    let sections = info_state.sections.iter();
+    let ordered = INFO_SECTIONS
+        .iter()
+        .filter_map(|name| sections.clone().find(|section| section.name == *name))
+        .chain(
+            sections
+                .clone()
+                .filter(|section| !INFO_SECTIONS.contains(&section.name.as_str())),
+        );
+
+    let tables = ordered.fold(
+        Scrollable::new(&mut info_state.scrollbar_state)
+            .padding(10)
+            .spacing(20)
+            .width(Length::Fill)
+            .height(Length::Fill),
+        |scrollable, section| {
+            let table = section
+                .entries
+                .iter()
+                .filter(|(field, _)| !field.ends_with("_human"))
+                .fold(
+                    Column::new().push(Text::new(section.name.to_uppercase()).size(24)),
+                    |col, (field, value)| {
+                        col.push(
+                            Row::new()
+                                .spacing(10)
+                                .push(Text::new(field.clone()).width(Length::FillPortion(1)))
+                                .push(
+                                    Text::new(format_info_value(field, value))
+                                        .width(Length::FillPortion(2)),
+                                ),
+                        )
+                    },
+                );
+            scrollable.push(table)
+        },
+    );
+
+    Column::new()
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .push(controls)
+        .push(tables)
+}
+
+fn create_metrics_view(metrics_state: &mut MetricsState) -> Column<Message> {
+    let pause_text = if metrics_state.is_paused {
+        "Resume"
+    } else {
+        "Pause"
+    };
+
+    let controls = Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(
+            Button::new(&mut metrics_state.pause_button_state, Text::new(pause_text))
+                .on_press(Message::MetricsPauseToggled),
+        )
+        .push(PickList::new(
+            &mut metrics_state.window_list_state,
+            &ChartWindow::ALL[..],
+            Some(metrics_state.window),
+            Message::MetricsWindowChanged,
+        ));
+
+    let history = &metrics_state.history;
+    let window = metrics_state.window;
+    let charts = Metric::ALL.chunks(2).fold(
+        Column::new().spacing(10).height(Length::Fill),
+        |col, metrics| {
+            col.push(metrics.iter().fold(
+                Row::new().spacing(10).height(Length::Fill),
+                |row, metric| {
+                    row.push(
+                        Canvas::new(MetricChart::new(history, *metric, window))
+                            .width(Length::Fill)
+                            .height(Length::Fill),
+                    )
+                },
+            ))
+        },
+    );
+
+    Column::new()
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .push(controls)
+        .push(charts)
+}
+
+fn create_slowlog_view(slowlog_state: &mut SlowlogState) -> Column<Message> {
+    let controls = Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(Text::new("Count"))
+        .push(
+            TextInput::new(
+                &mut slowlog_state.count_input_state,
+                "Entries to fetch",
+                &slowlog_state.count_value,
+                Message::SlowlogCountChanged,
+            )
+            .width(Length::Units(80))
+            .padding(5),
+        )
+        .push(
+            Button::new(&mut slowlog_state.fetch_button_state, Text::new("Fetch"))
+                .on_press(Message::FetchSlowlog),
+        )
+        .push(
+            Button::new(&mut slowlog_state.reset_button_state, Text::new("Reset"))
+                .on_press(Message::ResetSlowlog),
+        )
+        .push(
+            TextInput::new(
+                &mut slowlog_state.filter_input_state,
+                "Filter by command name",
+                &slowlog_state.filter_value,
+                Message::SlowlogFilterChanged,
+            )
+            .width(Length::Fill)
+            .padding(5),
+        );
+
+    let threshold_controls = Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(Text::new(format!("{} (µs)", SLOWLOG_THRESHOLD_CONFIG)))
+        .push(
+            TextInput::new(
+                &mut slowlog_state.threshold_input_state,
+                "Threshold in microseconds",
+                &slowlog_state.threshold_value,
+                Message::SlowlogThresholdChanged,
+            )
+            .width(Length::Units(120))
+            .padding(5),
+        )
+        .push(
+            Button::new(
+                &mut slowlog_state.threshold_button_state,
+                Text::new("Apply"),
+            )
+            .on_press(Message::SaveSlowlogThreshold),
+        )
+        .push(Text::new(&slowlog_state.status));
+
+    let sort_column = slowlog_state.sort_column;
+    let sort_descending = slowlog_state.sort_descending;
+    let header = slowlog_state.sort_buttons.iter_mut().fold(
+        Row::new().spacing(5),
+        |row, (column, state)| {
+            let label = if *column == sort_column {
+                format!("{} {}", column, if sort_descending { "v" } else { "^" })
+            } else {
+                column.to_string()
+            };
+            row.push(
+                Button::new(state, Text::new(label))
+                    .width(column.width())
+                    .on_press(Message::SlowlogSortChanged(*column)),
+            )
+        },
+    );
+
+    let filter = slowlog_state.filter_value.to_lowercase();
+    let rows = slowlog_state
+        .entries
+        .iter()
+        .filter(|entry| {
+            filter.is_empty()
+                || entry
+                    .command
+                    .first()
+                    .is_some_and(|name| name.to_lowercase().contains(&filter))
+        })
+        .fold(
+            Scrollable::new(&mut slowlog_state.scrollbar_state)
+                .spacing(5)
+                .width(Length::Fill)
+                .height(Length::Fill),
+            |scrollable, entry| {
+                let client = if entry.client_name.is_empty() {
+                    entry.client_addr.clone()
+                } else {
+                    format!("{} ({})", entry.client_addr, entry.client_name)
+                };
+                scrollable.push(
+                    Row::new()
+                        .spacing(5)
+                        .push(Text::new(entry.id.to_string()).width(SlowlogColumn::Id.width()))
+                        .push(
+                            Text::new(format_timestamp(entry.timestamp))
+                                .width(SlowlogColumn::Timestamp.width()),
+                        )
+                        .push(
+                            Text::new(format_micros(entry.duration_micros))
+                                .width(SlowlogColumn::Duration.width()),
+                        )
+                        .push(Text::new(client).width(SlowlogColumn::Client.width()))
+                        .push(
+                            Text::new(entry.command.join(" "))
+                                .width(SlowlogColumn::Command.width()),
+                        ),
+                )
+            },
+        );
+
+    Column::new()
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .push(controls)
+        .push(threshold_controls)
+        .push(header)
+        .push(rows)
+}
+
+fn create_clients_view(clients_state: &mut ClientsState) -> Column<Message> {
+    let controls = Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(
+            Button::new(
+                &mut clients_state.refresh_button_state,
+                Text::new("Refresh"),
+            )
+            .on_press(Message::RefreshClients),
+        )
+        .push(
+            TextInput::new(
+                &mut clients_state.filter_input_state,
+                "Filter by name or address",
+                &clients_state.filter_value,
+                Message::ClientsFilterChanged,
+            )
+            .width(Length::Fill)
+            .padding(5),
+        );
+
+    let controls = match clients_state.selected {
+        Some(id) if clients_state.is_confirming_kill => controls
+            .push(Text::new(format!("Kill client {}?", id)))
+            .push(
+                Button::new(
+                    &mut clients_state.confirm_button_state,
+                    Text::new("Confirm"),
+                )
+                .on_press(Message::ConfirmKillClient),
+            )
+            .push(
+                Button::new(&mut clients_state.cancel_button_state, Text::new("Cancel"))
+                    .on_press(Message::CancelKillClient),
+            ),
+        // killing our own connection would leave the tab without one
+        Some(id) if Some(id) == clients_state.own_id => controls
+            .push(Button::new(
+                &mut clients_state.kill_button_state,
+                Text::new("Kill"),
+            ))
+            .push(Text::new("This is the viewer's own connection")),
+        Some(_) => controls.push(
+            Button::new(&mut clients_state.kill_button_state, Text::new("Kill"))
+                .on_press(Message::KillClient),
+        ),
+        None => controls,
+    };
+
+    let controls = controls.push(Text::new(&clients_state.status));
+
+    let sort_column = clients_state.sort_column;
+    let sort_descending = clients_state.sort_descending;
+    let header = clients_state.sort_buttons.iter_mut().fold(
+        Row::new().spacing(5),
+        |row, (column, state)| {
+            let label = if *column == sort_column {
+                format!("{} {}", column, if sort_descending { "v" } else { "^" })
+            } else {
+                column.to_string()
+            };
+            row.push(
+                Button::new(state, Text::new(label))
+                    .width(column.width())
+                    .on_press(Message::ClientsSortChanged(*column)),
+            )
+        },
+    );
+
+    let filter = clients_state.filter_value.to_lowercase();
+    let selected = clients_state.selected;
+    let own_id = clients_state.own_id;
+    let rows = clients_state
+        .clients
+        .iter()
+        .zip(clients_state.row_buttons.iter_mut())
+        .filter(|(client, _)| {
+            filter.is_empty()
+                || client.name.to_lowercase().contains(&filter)
+                || client.addr.to_lowercase().contains(&filter)
+        })
+        .fold(
+            Scrollable::new(&mut clients_state.scrollbar_state)
+                .spacing(2)
+                .width(Length::Fill)
+                .height(Length::Fill),
+            |scrollable, (client, (id, state))| {
+                let marker = if Some(*id) == selected { "> " } else { "" };
+                let name = if Some(*id) == own_id {
+                    format!("{} (this viewer)", client.name)
+                } else {
+                    client.name.clone()
+                };
+                let row = Row::new()
+                    .spacing(5)
+                    .push(
+                        Text::new(format!("{}{}", marker, client.id))
+                            .width(ClientColumn::Id.width()),
+                    )
+                    .push(Text::new(client.addr.clone()).width(ClientColumn::Addr.width()))
+                    .push(Text::new(name).width(ClientColumn::Name.width()))
+                    .push(Text::new(format_duration(client.age)).width(ClientColumn::Age.width()))
+                    .push(Text::new(format_duration(client.idle)).width(ClientColumn::Idle.width()))
+                    .push(Text::new(client.db.to_string()).width(ClientColumn::Db.width()))
+                    .push(Text::new(client.cmd.clone()).width(ClientColumn::Cmd.width()))
+                    .push(
+                        Text::new(format_bytes(client.memory)).width(ClientColumn::Memory.width()),
+                    );
+                scrollable.push(
+                    Button::new(state, row)
+                        .width(Length::Fill)
+                        .on_press(Message::ClientSelected(*id)),
+                )
+            },
+        );
+
+    Column::new()
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .push(controls)
+        .push(header)
+        .push(rows)
+}
+
+fn create_monitor_view(monitor_state: &mut MonitorState) -> Column<Message> {
+    // newest entries first so the latest commands are visible without scrolling
+    let visible: Vec<&MonitorEntry> = monitor_state
+        .entries
+        .iter()
+        .rev()
+        .filter(|entry| monitor_state.is_visible(entry))
+        .collect();
+
+    let start_text = if monitor_state.is_running {
+        "Stop"
+    } else {
+        "Start"
+    };
+    let pause_text = if monitor_state.is_paused {
+        "Resume"
+    } else {
+        "Pause"
+    };
+
+    let controls = Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(
+            Button::new(&mut monitor_state.start_button_state, Text::new(start_text))
+                .on_press(Message::ToggleMonitor),
+        )
+        .push(
+            Button::new(&mut monitor_state.pause_button_state, Text::new(pause_text))
+                .on_press(Message::ToggleMonitorPause),
+        )
+        .push(
+            Button::new(&mut monitor_state.clear_button_state, Text::new("Clear"))
+                .on_press(Message::ClearMonitor),
+        )
+        .push(
+            TextInput::new(
+                &mut monitor_state.export_path_input_state,
+                "Export file path",
+                &monitor_state.export_path_value,
+                Message::MonitorExportPathChanged,
+            )
+            .width(Length::Units(200))
+            .padding(5),
+        )
+        .push(
+            Button::new(&mut monitor_state.export_button_state, Text::new("Export"))
+                .on_press(Message::ExportMonitor),
+        )
+        .push(Text::new(&monitor_state.status));
+
+    let filters = Row::new()
+        .padding(5)
+        .spacing(10)
+        .push(
+            TextInput::new(
+                &mut monitor_state.command_filter_input_state,
+                "Filter by command",
+                &monitor_state.command_filter_value,
+                Message::MonitorCommandFilterChanged,
+            )
+            .padding(5),
+        )
+        .push(
+            TextInput::new(
+                &mut monitor_state.key_filter_input_state,
+                "Filter by key pattern",
+                &monitor_state.key_filter_value,
+                Message::MonitorKeyFilterChanged,
+            )
+            .padding(5),
+        )
+        .push(
+            TextInput::new(
+                &mut monitor_state.client_filter_input_state,
+                "Filter by client",
+                &monitor_state.client_filter_value,
+                Message::MonitorClientFilterChanged,
+            )
+            .padding(5),
+        );
+
+    let rows = visible.into_iter().fold(
+        Scrollable::new(&mut monitor_state.scrollbar_state)
+            .spacing(2)
+            .width(Length::Fill)
+            .height(Length::Fill),
+        |scrollable, entry| {
+            scrollable.push(
+                Row::new()
+                    .spacing(5)
+                    .push(
+                        Text::new(format_timestamp_millis(entry.timestamp))
+                            .width(Length::FillPortion(2)),
+                    )
+                    .push(
+                        Text::new(format!("[{}] {}", entry.db, entry.client))
+                            .width(Length::FillPortion(2)),
+                    )
+                    .push(
+                        Text::new(format!("{} {}", entry.command, entry.args.join(" ")))
+                            .width(Length::FillPortion(6)),
+                    ),
+            )
+        },
+    );
+
+    Column::new()
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .push(controls)
+        .push(filters)
+        .push(rows)
+}
+
+fn create_key_grouping_controls(key_grouping_state: &mut KeyGroupingState) -> Row<Message> {
+    let sort_text = if key_grouping_state.sort_descending {
+        "Z-A"
+    } else {
+        "A-Z"
+    };
+
+    Row::new()
+        .padding(5)
+        .spacing(5)
+        .push(
+            TextInput::new(
+                &mut key_grouping_state.delimiters_input_state,
+                "Delimiters (empty for a flat list)",
+                &key_grouping_state.delimiters_value,
+                Message::TabDelimitersChanged,
+            )
+            .padding(5),
+        )
+        .push(
+            Button::new(
+                &mut key_grouping_state.apply_button_state,
+                Text::new("Group"),
+            )
+            .on_press(Message::ApplyTabDelimiters),
+        )
+        .push(
+            Button::new(
+                &mut key_grouping_state.sort_button_state,
+                Text::new(sort_text),
+            )
+            .on_press(Message::ToggleKeySort),
+        )
+        .push(PickList::new(
+            &mut key_grouping_state.type_filter_list_state,
+            &KeyTypeFilter::ALL[..],
+            Some(key_grouping_state.type_filter),
+            Message::KeyTypeFilterChanged,
+        ))
+}
+
+fn create_tabs_view<'a>(
+    tab_buttons: &'a mut [TabButtons],
+    tab_rename_state: &'a mut TabRenameState,
+    server_tabs: &Arena<ServerTab>,
+    current_server_tab_index: Option<Index>,
+) -> Row<'a, Message> {
+    let renaming = tab_rename_state.renaming;
+    let name_value = &tab_rename_state.name_value;
+    let mut name_input_state = Some(&mut tab_rename_state.name_input_state);
+    let tab_count = tab_buttons.len();
+
+    let mut tabs = Row::new()
+        .align_items(Align::Center)
+        .spacing(2)
+        .width(Length::Fill)
+        .height(Length::Shrink);
+    for (position, tab) in tab_buttons.iter_mut().enumerate() {
+        let name = match server_tabs.get(tab.index) {
+            Some(server_tab) => server_tab.name.clone(),
+            None => continue,
+        };
+        let index = tab.index;
+        let is_active = current_server_tab_index == Some(index);
+
+        tabs = match name_input_state.take() {
+            Some(input_state) if renaming == Some(index) => tabs.push(
+                TextInput::new(input_state, "Tab name", name_value, Message::TabNameChanged)
+                    .on_submit(Message::RenameTab)
+                    .width(Length::Units(150))
+                    .padding(5),
+            ),
+            input_state => {
+                name_input_state = input_state;
+                tabs.push(
+                    Button::new(&mut tab.select, Text::new(name))
+                        .style(style::TabButton { is_active })
+                        .on_press(Message::ChangeTab(index)),
+                )
+            }
+        };
+
+        if is_active {
+            let mut move_left = Button::new(&mut tab.move_left, Text::new("<"));
+            if position > 0 {
+                move_left = move_left.on_press(Message::MoveTabLeft(index));
+            }
+            let mut move_right = Button::new(&mut tab.move_right, Text::new(">"));
+            if position + 1 < tab_count {
+                move_right = move_right.on_press(Message::MoveTabRight(index));
+            }
+            let rename = if renaming == Some(index) {
+                Button::new(&mut tab.rename, Text::new("Save")).on_press(Message::RenameTab)
+            } else {
+                Button::new(&mut tab.rename, Text::new("Rename"))
+                    .on_press(Message::StartRenameTab(index))
+            };
+            tabs = tabs.push(move_left).push(move_right).push(rename);
+        }
+        tabs = tabs
+            .push(Button::new(&mut tab.close, Text::new("x")).on_press(Message::CloseTab(index)));
+    }
+    tabs
+}
+
+fn create_db_switcher(keyspace_state: &mut KeyspaceState, db: i64) -> Row<Message> {
+    let selected = keyspace_state
+        .databases
+        .iter()
+        .find(|keyspace_db| keyspace_db.db == db)
+        .cloned();
+    Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(Text::new(format!("db {}", db)))
+        .push(PickList::new(
+            &mut keyspace_state.db_list_state,
+            keyspace_state.databases.clone(),
+            selected,
+            Message::DbPicked,
+        ))
+        .push(
+            TextInput::new(
+                &mut keyspace_state.db_input_state,
+                "db",
+                &keyspace_state.db_value,
+                Message::SwitchDbValueChanged,
+            )
+            .width(Length::Units(50))
+            .padding(5),
+        )
+        .push(
+            Button::new(&mut keyspace_state.select_button_state, Text::new("Select"))
+                .on_press(Message::SwitchDb),
+        )
+        .push(Text::new(&keyspace_state.status))
+}
+
+fn create_live_keys_controls(live_keys_state: &mut LiveKeysState) -> Row<Message> {
+    let toggle_text = if live_keys_state.is_enabled {
+        "Stop Live"
+    } else {
+        "Go Live"
+    };
+
+    Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(
+            Button::new(
+                &mut live_keys_state.toggle_button_state,
+                Text::new(toggle_text),
+            )
+            .on_press(Message::ToggleLiveKeys),
+        )
+        .push(Checkbox::new(
+            live_keys_state.configure_notifications,
+            "Enable keyspace notifications",
+            Message::ConfigureNotificationsToggled,
+        ))
+        .push(Text::new(&live_keys_state.status))
+}
+
+fn create_memory_view(memory_state: &mut MemoryState) -> Column<Message> {
+    let total_bytes = memory_state.total_bytes().max(1);
+    let scan_button = if memory_state.is_running {
+        Button::new(&mut memory_state.cancel_button_state, Text::new("Cancel"))
+            .on_press(Message::CancelMemoryScan)
+    } else {
+        Button::new(&mut memory_state.scan_button_state, Text::new("Scan"))
+            .on_press(Message::StartMemoryScan)
+    };
+    // a new precision restarts the scan, so it's fixed while one runs
+    let precision: Element<Message> = if memory_state.is_running {
+        Text::new(memory_state.precision.to_string()).into()
+    } else {
+        PickList::new(
+            &mut memory_state.precision_list_state,
+            &MemoryPrecision::ALL[..],
+            Some(memory_state.precision),
+            Message::MemoryPrecisionChanged,
+        )
+        .into()
+    };
+    let controls = Row::new()
+        .padding(5)
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(precision)
+        .push(Text::new("Top"))
+        .push(
+            TextInput::new(
+                &mut memory_state.top_input_state,
+                "Keys",
+                &memory_state.top_value,
+                Message::MemoryTopChanged,
+            )
+            .width(Length::Units(60))
+            .padding(5),
+        )
+        .push(scan_button)
+        .push(
+            Button::new(
+                &mut memory_state.treemap_button_state,
+                Text::new(if memory_state.show_treemap {
+                    "Show table"
+                } else {
+                    "Show treemap"
+                }),
+            )
+            .on_press(Message::ToggleMemoryTreemap),
+        )
+        .push(Text::new(&memory_state.status));
+
+    if memory_state.show_treemap {
+        let children = memory_tree_children(&memory_state.tree, &memory_state.treemap_path)
+            .unwrap_or(&memory_state.tree[..]);
+        let up_button = Button::new(&mut memory_state.treemap_up_button_state, Text::new("Up"));
+        let up_button = if memory_state.treemap_path.is_empty() {
+            up_button
+        } else {
+            up_button.on_press(Message::MemoryTreemapUp)
+        };
+        let location = if memory_state.treemap_path.is_empty() {
+            "All keys".to_string()
+        } else {
+            memory_state.treemap_path.join(" > ")
+        };
+        return Column::new()
+            .push(controls)
+            .push(
+                Row::new()
+                    .padding(5)
+                    .spacing(10)
+                    .align_items(Align::Center)
+                    .push(up_button)
+                    .push(Text::new(location)),
+            )
+            .push(
+                Canvas::new(MemoryTreemap::new(
+                    children,
+                    Message::MemoryTreemapDrillDown,
+                    Message::OpenMemoryKey,
+                ))
+                .width(Length::Fill)
+                .height(Length::Fill),
+            );
+    }
+
+    let namespace_header = Row::new()
+        .spacing(5)
+        .push(Text::new("Namespace").width(Length::FillPortion(4)))
+        .push(Text::new("Memory").width(Length::FillPortion(1)))
+        .push(Text::new("Keys").width(Length::FillPortion(1)))
+        .push(Text::new("Share").width(Length::FillPortion(1)));
+    let namespaces = memory_state
+        .namespaces
+        .iter()
+        .take(MAX_MEMORY_NAMESPACE_ROWS)
+        .fold(
+            Scrollable::new(&mut memory_state.namespaces_scrollbar_state)
+                .spacing(5)
+                .width(Length::Fill)
+                .height(Length::Fill),
+            |scrollable, namespace| {
+                let name = match namespace.path.last() {
+                    Some(name) if !name.is_empty() => name.clone(),
+                    _ => "(no namespace)".into(),
+                };
+                scrollable.push(
+                    Row::new()
+                        .spacing(5)
+                        .push(
+                            Row::new()
+                                .width(Length::FillPortion(4))
+                                .push(Space::with_width(Length::Units(
+                                    (namespace.path.len() - 1) as u16 * 10,
+                                )))
+                                .push(Text::new(name)),
+                        )
+                        .push(
+                            Text::new(format_bytes(namespace.bytes)).width(Length::FillPortion(1)),
+                        )
+                        .push(Text::new(namespace.keys.to_string()).width(Length::FillPortion(1)))
+                        .push(
+                            Text::new(format!(
+                                "{:.1}%",
+                                namespace.bytes as f64 * 100.0 / total_bytes as f64
+                            ))
+                            .width(Length::FillPortion(1)),
+                        ),
+                )
+            },
+        );
+
+    let top_keys_header = Row::new()
+        .spacing(5)
+        .push(Text::new("Key").width(Length::FillPortion(4)))
+        .push(Text::new("Type").width(Length::FillPortion(1)))
+        .push(Text::new("Memory").width(Length::FillPortion(1)))
+        .push(Text::new("Elements").width(Length::FillPortion(1)));
+    let top_keys = memory_state.top_keys.iter().fold(
+        Scrollable::new(&mut memory_state.top_keys_scrollbar_state)
+            .spacing(5)
+            .width(Length::Fill)
+            .height(Length::Fill),
+        |scrollable, key_memory| {
+            scrollable.push(
+                Row::new()
+                    .spacing(5)
+                    .push(Text::new(key_memory.key.clone()).width(Length::FillPortion(4)))
+                    .push(Text::new(key_memory.key_type.clone()).width(Length::FillPortion(1)))
+                    .push(Text::new(format_bytes(key_memory.bytes)).width(Length::FillPortion(1)))
+                    .push(
+                        Text::new(
+                            key_memory
+                                .elements
+                                .map_or(String::new(), |elements| elements.to_string()),
+                        )
+                        .width(Length::FillPortion(1)),
+                    ),
+            )
+        },
+    );
+
+    Column::new().push(controls).push(
+        Row::new()
+            .spacing(20)
+            .height(Length::Fill)
+            .push(
+                Column::new()
+                    .width(Length::Fill)
+                    .push(namespace_header)
+                    .push(namespaces),
+            )
+            .push(
+                Column::new()
+                    .width(Length::Fill)
+                    .push(top_keys_header)
+                    .push(top_keys),
+            ),
+    )
+}
+
+fn create_pubsub_view(pubsub_state: &mut PubSubState) -> Row<Message> {
+    let subscribe_controls = Row::new()
+        .spacing(5)
+        .push(
+            TextInput::new(
+                &mut pubsub_state.subscribe_input_state,
+                "Channel or pattern",
+                &pubsub_state.subscribe_value,
+                Message::PubSubSubscribeChanged,
+            )
+            .padding(5),
+        )
+        .push(
+            Button::new(
+                &mut pubsub_state.subscribe_button_state,
+                Text::new("Subscribe"),
+            )
+            .on_press(Message::SubscribeChannel),
+        )
+        .push(
+            Button::new(
+                &mut pubsub_state.psubscribe_button_state,
+                Text::new("Pattern"),
+            )
+            .on_press(Message::SubscribePattern),
+        );
+
+    let subscriptions = pubsub_state.channels.iter_mut().fold(
+        Column::new()
+            .spacing(2)
+            .push(Text::new("Subscriptions").size(24)),
+        |col, (channel, state)| {
+            col.push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Align::Center)
+                    .push(Text::new(channel.clone()).width(Length::Fill))
+                    .push(
+                        Button::new(state, Text::new("Unsubscribe"))
+                            .on_press(Message::UnsubscribeChannel(channel.clone())),
+                    ),
+            )
+        },
+    );
+    let subscriptions =
+        pubsub_state
+            .patterns
+            .iter_mut()
+            .fold(subscriptions, |col, (pattern, state)| {
+                col.push(
+                    Row::new()
+                        .spacing(5)
+                        .align_items(Align::Center)
+                        .push(Text::new(format!("{} (pattern)", pattern)).width(Length::Fill))
+                        .push(
+                            Button::new(state, Text::new("Unsubscribe"))
+                                .on_press(Message::UnsubscribePattern(pattern.clone())),
+                        ),
+                )
+            });
+
+    let publish_controls = Column::new()
+        .spacing(5)
+        .push(Text::new("Publish").size(24))
+        .push(
+            TextInput::new(
+                &mut pubsub_state.publish_channel_input_state,
+                "Channel",
+                &pubsub_state.publish_channel_value,
+                Message::PublishChannelChanged,
+            )
+            .padding(5),
+        )
+        .push(
+            TextInput::new(
+                &mut pubsub_state.publish_message_input_state,
+                "Message",
+                &pubsub_state.publish_message_value,
+                Message::PublishMessageChanged,
+            )
+            .padding(5),
+        )
+        .push(
+            Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut pubsub_state.publish_button_state, Text::new("Publish"))
+                        .on_press(Message::PublishMessage),
+                )
+                .push(Text::new(&pubsub_state.status)),
+        );
+
+    let active_channels = pubsub_state.active_channels.iter().fold(
+        Scrollable::new(&mut pubsub_state.channels_scrollbar_state)
+            .spacing(2)
+            .width(Length::Fill)
+            .height(Length::Fill),
+        |scrollable, (channel, subscribers)| {
+            scrollable.push(
+                Row::new()
+                    .spacing(5)
+                    .push(Text::new(channel.clone()).width(Length::FillPortion(3)))
+                    .push(
+                        Text::new(format!("{} subscribers", subscribers))
+                            .width(Length::FillPortion(1)),
+                    ),
+            )
+        },
+    );
+    let active_channels = Column::new()
+        .spacing(5)
+        .height(Length::Fill)
+        .push(Text::new("Active channels").size(24))
+        .push(
+            Row::new()
+                .spacing(5)
+                .push(
+                    TextInput::new(
+                        &mut pubsub_state.list_pattern_input_state,
+                        "Channel pattern",
+                        &pubsub_state.list_pattern_value,
+                        Message::PubSubListPatternChanged,
+                    )
+                    .padding(5),
+                )
+                .push(
+                    Button::new(&mut pubsub_state.list_button_state, Text::new("List"))
+                        .on_press(Message::ListPubSubChannels),
+                ),
+        )
+        .push(active_channels);
+
+    let sidebar = Column::new()
+        .width(Length::FillPortion(2))
+        .height(Length::Fill)
+        .spacing(20)
+        .padding(5)
+        .push(subscribe_controls)
+        .push(subscriptions)
+        .push(publish_controls)
+        .push(active_channels);
+
+    // newest messages first so the latest traffic is visible without scrolling
+    let messages = pubsub_state.messages.iter().rev().fold(
+        Scrollable::new(&mut pubsub_state.messages_scrollbar_state)
+            .spacing(2)
+            .width(Length::Fill)
+            .height(Length::Fill),
+        |scrollable, message| {
+            let channel = match &message.pattern {
+                Some(pattern) => format!("{} ({})", message.channel, pattern),
+                None => message.channel.clone(),
+            };
+            scrollable.push(
+                Row::new()
+                    .spacing(5)
+                    .push(
+                        Text::new(format_timestamp_millis(message.received_at))
+                            .width(Length::FillPortion(2)),
+                    )
+                    .push(Text::new(channel).width(Length::FillPortion(2)))
+                    .push(Text::new(message.payload.clone()).width(Length::FillPortion(5))),
+            )
+        },
+    );
+    let messages = Column::new()
+        .width(Length::FillPortion(3))
+        .height(Length::Fill)
+        .padding(5)
+        .spacing(5)
+        .push(
+            Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(Text::new("Messages").size(24))
+                .push(
+                    Button::new(&mut pubsub_state.clear_button_state, Text::new("Clear"))
+                        .on_press(Message::ClearPubSubMessages),
+                ),
+        )
+        .push(messages);
+
+    Row::new()
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .push(sidebar)
+        .push(messages)
+}
+
+impl Application for RedisViewer {
+    type Message = Message;
+    type Executor = executor::Default;
+    type Flags = ();
+
+    fn new(_flags: ()) -> (Self, Command<Message>) {
+        let server_tabs = Arena::<ServerTab>::new();
+
+        let current_server_tab_index = None;
+
+        let conn_form_state = ConnectionFormState {
+            show_connection_form: true,
+            conn_name_text_input_state: text_input::State::default(),
+            conn_name_value: String::from("localhost"),
+            conn_text_input_state: text_input::State::default(),
+            conn_value: String::from("127.0.0.1"),
+            connect_button: button::State::default(),
+            test_button: button::State::default(),
+            port_text_input_state: text_input::State::default(),
+            port_value: String::from("6379"),
+            db_text_input_state: text_input::State::default(),
+            db_value: String::from("0"),
+            delimiters_text_input_state: text_input::State::default(),
+            delimiters_value: String::from(DEFAULT_NAMESPACE_DELIMITERS),
+            connect_timeout_text_input_state: text_input::State::default(),
+            connect_timeout_value: String::from(DEFAULT_CONNECT_TIMEOUT_MILLIS),
+            read_timeout_text_input_state: text_input::State::default(),
+            read_timeout_value: String::from(DEFAULT_IO_TIMEOUT_MILLIS),
+            write_timeout_text_input_state: text_input::State::default(),
+            write_timeout_value: String::from(DEFAULT_IO_TIMEOUT_MILLIS),
+            rdb_path_text_input_state: text_input::State::default(),
+            rdb_path_value: String::from("dump.rdb"),
+            open_rdb_button: button::State::default(),
+            status: String::new(),
+        };
+
+        let keys_refresh_button_state = button::State::default();
+        let tab_buttons = Vec::<TabButtons>::new();
+        let new_tab_button = button::State::default();
+        let create_key_button = button::State::default();
+        let panel_buttons = TabPanel::ALL
+            .iter()
+            .map(|panel| (*panel, button::State::default()))
+            .collect();
+
+        (
+            Self {
+                server_tabs,
+                current_server_tab_index,
+                conn_form_state,
+                keys_refresh_button_state,
+                tab_buttons,
+                tab_rename_state: TabRenameState::new(),
+                new_tab_button,
+                create_key_button,
+                panel_buttons,
+                modifiers: keyboard::Modifiers::default(),
+                is_dragging: false,
+            },
+            Command::none(),
+        )
+    }
+
+    fn title(&self) -> String {
+        String::from("Icy Redis Viewer")
+    }
+
+    fn update(&mut self, message: Self::Message, _clipboard: &mut Clipboard) -> Command<Message> {
+        match message {
+            Message::KeySelected(key) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                if self.modifiers.shift && !current_server_tab.is_read_only() {
+                    current_server_tab.select_key_range(key);
+                } else {
+                    current_server_tab.open_key(key);
+                }
+            }
+            Message::SelectedValueChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                match &mut current_server_tab.editor_state {
+                    EditorState::Empty => {}
+                    EditorState::Edit(edit_state) => {
+                        edit_state.value = s;
+                    }
+                    EditorState::Create(_) => {}
+                    EditorState::Inspect(_) => {}
+                }
+            }
+            Message::SelectedValueSaved => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let saved = match &current_server_tab.editor_state {
+                    EditorState::Edit(edit_state) => current_server_tab
+                        .source
+                        .server_connection()
+                        .and_then(|con| {
+                            set_redis_value(con, edit_state.key.clone(), edit_state.value.clone())
+                        }),
+                    EditorState::Empty | EditorState::Create(_) | EditorState::Inspect(_) => Ok(()),
+                };
+                match saved {
+                    Ok(()) => {
+                        if let EditorState::Edit(edit_state) = &mut current_server_tab.editor_state
+                        {
+                            edit_state.saved_value = edit_state.value.clone();
+                        }
+                    }
+                    Err(e) => current_server_tab.command_failed("save the value", e),
+                }
+            }
+            Message::SelectedValueReloaded => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                if let Some(key) = current_server_tab.editor_state.key().map(String::from) {
+                    current_server_tab.status.clear();
+                    current_server_tab.open_key(key);
+                }
+            }
+            Message::SelectedValueDeleted => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+
+                let deleted = match &current_server_tab.editor_state {
+                    EditorState::Edit(edit_state) => current_server_tab
+                        .source
+                        .server_connection()
+                        .and_then(|con| delete_redis_key(con, edit_state.key.clone()))
+                        .map(|()| true),
+                    EditorState::Empty | EditorState::Create(_) | EditorState::Inspect(_) => {
+                        Ok(false)
+                    }
+                };
+                match deleted {
+                    Ok(true) => current_server_tab.editor_state = EditorState::Empty,
+                    Ok(false) => {}
+                    Err(e) => current_server_tab.command_failed("delete the key", e),
+                }
+                self.refresh_keys();
+            }
+            Message::ConnNameChanged(s) => {
+                self.conn_form_state.conn_name_value = s;
+            }
+            Message::ConnValueChanged(s) => {
+                self.conn_form_state.conn_value = s;
+            }
+            Message::PortValueChanged(s) => {
+                self.conn_form_state.port_value = s;
+            }
+            Message::DbValueChanged(s) => {
+                self.conn_form_state.db_value = s;
+            }
+            Message::DelimitersValueChanged(s) => {
+                self.conn_form_state.delimiters_value = s;
+            }
+            Message::TabDelimitersChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.key_grouping_state.delimiters_value = s;
+            }
+            Message::ApplyTabDelimiters => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let key_grouping_state = &mut current_server_tab.key_grouping_state;
+                key_grouping_state.delimiters = key_grouping_state.delimiters_value.clone();
+                current_server_tab.regroup_keys();
+                current_server_tab.load_key_metadata();
+            }
+            Message::ToggleKeySort => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.toggle_key_sort();
+                current_server_tab.load_key_metadata();
+            }
+            Message::DeletePatternChanged(pattern) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let pattern_delete_state = &mut current_server_tab.pattern_delete_state;
+                pattern_delete_state.pattern_value = pattern;
+                pattern_delete_state.namespace_patterns.clear();
+            }
+            Message::PreviewPatternDelete => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let pattern_delete_state = &mut current_server_tab.pattern_delete_state;
+                if !pattern_delete_state.pattern_value.is_empty() {
+                    pattern_delete_state.start(true);
+                }
+            }
+            Message::RunPatternDelete => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.pattern_delete_state.start(false);
+            }
+            Message::CancelPatternDelete => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let pattern_delete_state = &mut current_server_tab.pattern_delete_state;
+                pattern_delete_state.running_patterns = None;
+                if pattern_delete_state.is_dry_run {
+                    pattern_delete_state.status = "Preview cancelled".into();
+                } else {
+                    pattern_delete_state.status = format!(
+                        "Cancelled after deleting {} keys",
+                        pattern_delete_state.progress.deleted
+                    );
+                    current_server_tab.reload_keys();
+                }
+            }
+            Message::DeleteNamespace(indices) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let patterns = current_server_tab.namespace_patterns_at(&indices);
+                let pattern_delete_state = &mut current_server_tab.pattern_delete_state;
+                pattern_delete_state.set_namespace_patterns(patterns);
+                pattern_delete_state.start(true);
+            }
+            Message::ExportPathChanged(path) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.keys_export_state.path_value = path;
+            }
+            Message::ExportSelectedKeys => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let mut keys: Vec<String> = current_server_tab
+                    .key_selection_state
+                    .selected
+                    .iter()
+                    .cloned()
+                    .collect();
+                sort_keys(&mut keys, false);
+                current_server_tab.export_keys(&keys);
+            }
+            Message::ExportPatternKeys => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let patterns = current_server_tab.pattern_delete_state.patterns();
+                match current_server_tab.scan_patterns(&patterns) {
+                    Ok(mut keys) => {
+                        sort_keys(&mut keys, false);
+                        current_server_tab.export_keys(&keys);
+                    }
+                    Err(e) => {
+                        current_server_tab.keys_export_state.status =
+                            format!("Failed to export: {}", e)
+                    }
+                }
+            }
+            Message::ExportNamespace(indices) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let patterns = current_server_tab.namespace_patterns_at(&indices);
+                match current_server_tab.scan_patterns(&patterns) {
+                    Ok(mut keys) => {
+                        sort_keys(&mut keys, false);
+                        current_server_tab.export_keys(&keys);
+                    }
+                    Err(e) => {
+                        current_server_tab.keys_export_state.status =
+                            format!("Failed to export: {}", e)
+                    }
+                }
+            }
+            Message::ExportFormatChanged(format) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let keys_export_state = &mut current_server_tab.keys_export_state;
+                if keys_export_state.path_value == keys_export_state.format.default_path() {
+                    keys_export_state.path_value = format.default_path().into();
+                }
+                keys_export_state.format = format;
+            }
+            Message::ImportFormatChanged(format) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let keys_import_state = &mut current_server_tab.keys_import_state;
+                if keys_import_state.path_value == keys_import_state.format.default_path() {
+                    keys_import_state.path_value = format.default_path().into();
+                }
+                keys_import_state.format = format;
+                keys_import_state.snapshot = None;
+                keys_import_state.commands = None;
+            }
+            Message::ImportPathChanged(path) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let keys_import_state = &mut current_server_tab.keys_import_state;
+                keys_import_state.path_value = path;
+                keys_import_state.snapshot = None;
+                keys_import_state.commands = None;
+            }
+            Message::ImportPolicyChanged(policy) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.keys_import_state.policy = policy;
+            }
+            Message::ImportSuffixChanged(suffix) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.keys_import_state.suffix_value = suffix;
+            }
+            Message::PreviewImport => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let keys_import_state = &mut current_server_tab.keys_import_state;
+                keys_import_state.snapshot = None;
+                keys_import_state.report = None;
+                keys_import_state.commands = None;
+                keys_import_state.commands_report = None;
+                if keys_import_state.format == FileFormat::Commands {
+                    keys_import_state.status = match fs::read(&keys_import_state.path_value)
+                        .map(|data| parse_commands(&data))
+                    {
+                        Ok(Ok(commands)) => {
+                            let status = format!("{} commands to run", commands.len());
+                            keys_import_state.commands = Some(commands);
+                            status
+                        }
+                        Ok(Err(e)) => {
+                            format!("Failed to parse {}: {}", keys_import_state.path_value, e)
+                        }
+                        Err(e) => {
+                            format!("Failed to read {}: {}", keys_import_state.path_value, e)
+                        }
+                    };
+                    return Command::none();
+                }
+                keys_import_state.status = match KeysSnapshot::read(&keys_import_state.path_value) {
+                    Ok(snapshot) => {
+                        match current_server_tab
+                            .source
+                            .server_connection()
+                            .and_then(|con| preview_import(con, &snapshot))
+                        {
+                            Ok(preview) => {
+                                keys_import_state.snapshot = Some((snapshot, preview));
+                                String::new()
+                            }
+                            Err(e) => format!("Failed to preview import: {}", e),
+                        }
+                    }
+                    Err(e) => format!("Failed to read {}: {}", keys_import_state.path_value, e),
+                };
+            }
+            Message::RunImport => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let con = match current_server_tab.source.connection() {
+                    Some(con) => con,
+                    None => return Command::none(),
+                };
+                let keys_import_state = &mut current_server_tab.keys_import_state;
+                if let Some((snapshot, _)) = keys_import_state.snapshot.take() {
+                    let report = import_keys(
+                        con,
+                        &snapshot,
+                        keys_import_state.policy,
+                        &keys_import_state.suffix_value,
+                    );
+                    keys_import_state.status = format!(
+                        "Imported {} keys ({} renamed), skipped {}, {} failed",
+                        report.written,
+                        report.renamed,
+                        report.skipped,
+                        report.failures.len()
+                    );
+                    keys_import_state.report = Some(report);
+                    current_server_tab.reload_keys();
+                } else if let Some(commands) = keys_import_state.commands.take() {
+                    let report = run_commands(con, &commands);
+                    keys_import_state.status = format!(
+                        "Ran {} commands, {} batches reported errors",
+                        report.executed,
+                        report.failures.len()
+                    );
+                    keys_import_state.commands_report = Some(report);
+                    current_server_tab.reload_keys();
+                }
+            }
+            Message::CopyTargetChanged(target) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.keys_copy_state.target = Some(target);
+            }
+            Message::CopyDbChanged(db) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.keys_copy_state.db_value = db;
+            }
+            Message::CopyReplaceToggled(replace) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.keys_copy_state.replace = replace;
+            }
+            Message::CopySelectedKeys => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let mut keys: Vec<String> = current_server_tab
+                    .key_selection_state
+                    .selected
+                    .iter()
+                    .cloned()
+                    .collect();
+                sort_keys(&mut keys, false);
+                self.copy_keys_from_current_tab(&keys);
+            }
+            Message::CopyNamespace(indices) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let patterns = current_server_tab.namespace_patterns_at(&indices);
+                match current_server_tab.scan_patterns(&patterns) {
+                    Ok(mut keys) => {
+                        sort_keys(&mut keys, false);
+                        self.copy_keys_from_current_tab(&keys);
+                    }
+                    Err(e) => {
+                        current_server_tab.keys_copy_state.status = format!("Failed to copy: {}", e)
+                    }
+                }
+            }
+            Message::PatternDeleteEventReceived(index, event) => {
+                if let Some(server_tab) = self.server_tabs.get_mut(index) {
+                    let pattern_delete_state = &mut server_tab.pattern_delete_state;
+                    let is_dry_run = pattern_delete_state.is_dry_run;
+                    match event {
+                        PatternDeleteEvent::Progress(progress) => {
+                            pattern_delete_state.status = if is_dry_run {
+                                format!("Scanning... {} keys match so far", progress.matched)
+                            } else {
+                                format!(
+                                    "Deleting... {} of {} matched keys deleted",
+                                    progress.deleted, progress.matched
+                                )
+                            };
+                            pattern_delete_state.progress = progress;
+                        }
+                        PatternDeleteEvent::Finished(progress) => {
+                            let patterns = pattern_delete_state
+                                .running_patterns
+                                .take()
+                                .unwrap_or_default();
+                            let pattern = patterns.join(", ");
+                            if is_dry_run {
+                                pattern_delete_state.status =
+                                    format!("{} keys match {}", progress.matched, pattern);
+                                if server_tab
+                                    .key_grouping_state
+                                    .type_filter
+                                    .type_name()
+                                    .is_some()
+                                {
+                                    pattern_delete_state
+                                        .status
+                                        .push_str(", of any type: the type filter is not applied");
+                                }
+                                pattern_delete_state.preview = Some((patterns, progress.clone()));
+                            } else {
+                                pattern_delete_state.status = format!(
+                                    "Deleted {} keys matching {}",
+                                    progress.deleted, pattern
+                                );
+                            }
+                            pattern_delete_state.progress = progress;
+                            if !is_dry_run {
+                                server_tab.reload_keys();
+                            }
+                        }
+                        PatternDeleteEvent::Failed(e) => {
+                            pattern_delete_state.running_patterns = None;
+                            pattern_delete_state.status = format!("Failed: {}", e);
+                            if !is_dry_run {
+                                server_tab.reload_keys();
+                            }
+                        }
+                    }
+                }
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+            }
+            Message::KeyChecked(key, is_selected) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                if self.modifiers.shift {
+                    current_server_tab.select_key_range(key);
+                } else {
+                    current_server_tab
+                        .key_selection_state
+                        .toggle(key, is_selected);
+                }
+            }
+            Message::ClearKeySelection => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let key_selection_state = &mut current_server_tab.key_selection_state;
+                key_selection_state.selected.clear();
+                key_selection_state.anchor = None;
+            }
+            Message::DeleteSelectedKeys => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let key_selection_state = &mut current_server_tab.key_selection_state;
+                key_selection_state.is_confirming_delete = !key_selection_state.selected.is_empty();
+                key_selection_state.preview_scrollbar_state = scrollable::State::default();
+            }
+            Message::CancelDeleteSelectedKeys => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.key_selection_state.is_confirming_delete = false;
+            }
+            Message::ConfirmDeleteSelectedKeys => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let key_selection_state = &mut current_server_tab.key_selection_state;
+                let keys: Vec<String> = key_selection_state.selected.iter().cloned().collect();
+                key_selection_state.is_confirming_delete = false;
+                key_selection_state.status = match current_server_tab
+                    .source
+                    .server_connection()
+                    .and_then(|con| unlink_keys(con, &keys))
+                {
+                    Ok(removed) => {
+                        key_selection_state.selected.clear();
+                        key_selection_state.anchor = None;
+                        format!("Deleted {} keys", removed)
+                    }
+                    Err(e) => format!("Failed to delete keys: {}", e),
+                };
+                self.refresh_keys();
+            }
+            Message::KeyTypeFilterChanged(type_filter) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.key_grouping_state.type_filter = type_filter;
+                self.refresh_keys();
+            }
+            Message::ConnectRedis => {
+                let settings = match self.conn_form_state.settings() {
+                    Ok(settings) => settings,
+                    Err(e) => {
+                        self.conn_form_state.status = e;
+                        return Command::none();
+                    }
+                };
+                let redis = match connect_redis(&settings) {
+                    Ok(redis) => redis,
+                    Err(e) => {
+                        self.conn_form_state.status = format!(
+                            "Failed to connect to {}:{}: {}",
+                            settings.address, settings.port, e
+                        );
+                        return Command::none();
+                    }
+                };
+                let delimiters = self.conn_form_state.delimiters_value.clone();
+                let name = self.conn_form_state.conn_name_value.clone();
+
+                // the keys are scanned in the background once the tab is open
+                let server_tab = ServerTab::new(
+                    name,
+                    KeySource::Server(redis, settings),
+                    Vec::new(),
+                    delimiters,
+                );
+                let index = self.server_tabs.insert(server_tab);
+                self.current_server_tab_index = Some(index);
+                self.tab_buttons.push(TabButtons::new(index));
+                self.conn_form_state.status.clear();
+                self.conn_form_state.show_connection_form = false;
+            }
+            Message::TestConnection => {
+                self.conn_form_state.status = match self.conn_form_state.settings() {
+                    Ok(settings) => match test_connection(&settings) {
+                        Ok(check) => check.to_string(),
+                        Err(e) => format!(
+                            "Failed to connect to {}:{}: {}",
+                            settings.address, settings.port, e
+                        ),
+                    },
+                    Err(e) => e,
+                };
+            }
+            Message::ConnectTimeoutChanged(s) => {
+                self.conn_form_state.connect_timeout_value = s;
+            }
+            Message::ReadTimeoutChanged(s) => {
+                self.conn_form_state.read_timeout_value = s;
+            }
+            Message::WriteTimeoutChanged(s) => {
+                self.conn_form_state.write_timeout_value = s;
+            }
+            Message::DbPicked(keyspace_db) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.switch_db(keyspace_db.db);
+            }
+            Message::SwitchDbValueChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.keyspace_state.db_value = s;
+            }
+            Message::SwitchDb => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                match current_server_tab
+                    .keyspace_state
+                    .db_value
+                    .trim()
+                    .parse::<i64>()
+                {
+                    Ok(db) => current_server_tab.switch_db(db),
+                    Err(_) => {
+                        current_server_tab.keyspace_state.status =
+                            "Enter the number of a db".into();
+                    }
+                }
+            }
+            Message::RdbPathChanged(s) => {
+                self.conn_form_state.rdb_path_value = s;
+            }
+            Message::OpenRdb => {
+                let path = self.conn_form_state.rdb_path_value.clone();
+                let rdb_file = match RdbFile::read(&path) {
+                    Ok(rdb_file) => rdb_file,
+                    Err(e) => {
+                        self.conn_form_state.status = format!("Failed to read {}: {}", path, e);
+                        return Command::none();
+                    }
+                };
+                if rdb_file.databases.is_empty() {
+                    self.conn_form_state.status = format!("{} holds no keys", path);
+                    return Command::none();
+                }
+
+                // every database of the dump gets a tab of its own
+                let file_name = Path::new(&path)
+                    .file_name()
+                    .map_or(path.clone(), |name| name.to_string_lossy().into_owned());
+                let is_single_db = rdb_file.databases.len() == 1;
+                let delimiters = self.conn_form_state.delimiters_value.clone();
+                let mut first_index = None;
+                for database in rdb_file.databases {
+                    let name = if is_single_db {
+                        file_name.clone()
+                    } else {
+                        format!("{} db{}", file_name, database.index)
+                    };
+                    let keys = database.keys(None);
+                    let server_tab =
+                        ServerTab::new(name, KeySource::Rdb(database), keys, delimiters.clone());
+                    let index = self.server_tabs.insert(server_tab);
+                    first_index.get_or_insert(index);
+                    self.tab_buttons.push(TabButtons::new(index));
+                }
+                self.current_server_tab_index = first_index;
+                self.conn_form_state.status.clear();
+                self.conn_form_state.show_connection_form = false;
+            }
+            Message::RefreshKeys => {
+                self.refresh_keys();
+            }
+            Message::ChangeTab(i) => {
+                self.current_server_tab_index = Some(i);
+            }
+            Message::CloseTab(index) => {
+                // dropping the tab closes its connection and ends its streams
+                self.server_tabs.remove(index);
+                let position = self
+                    .tab_buttons
+                    .iter()
+                    .position(|tab_buttons| tab_buttons.index == index);
+                if let Some(position) = position {
+                    self.tab_buttons.remove(position);
+                    if self.current_server_tab_index == Some(index) {
+                        let neighbour = position.min(self.tab_buttons.len().saturating_sub(1));
+                        self.current_server_tab_index = self
+                            .tab_buttons
+                            .get(neighbour)
+                            .map(|tab_buttons| tab_buttons.index);
+                    }
+                }
+                if self.tab_rename_state.renaming == Some(index) {
+                    self.tab_rename_state.renaming = None;
+                }
+                if self.current_server_tab_index.is_none() {
+                    self.conn_form_state.show_connection_form = true;
+                }
+            }
+            Message::MoveTabLeft(index) => {
+                let position = self
+                    .tab_buttons
+                    .iter()
+                    .position(|tab_buttons| tab_buttons.index == index);
+                if let Some(position) = position.filter(|position| *position > 0) {
+                    self.tab_buttons.swap(position, position - 1);
+                }
+            }
+            Message::MoveTabRight(index) => {
+                let position = self
+                    .tab_buttons
+                    .iter()
+                    .position(|tab_buttons| tab_buttons.index == index);
+                if let Some(position) =
+                    position.filter(|position| position + 1 < self.tab_buttons.len())
+                {
+                    self.tab_buttons.swap(position, position + 1);
+                }
+            }
+            Message::StartRenameTab(index) => {
+                if let Some(server_tab) = self.server_tabs.get(index) {
+                    self.tab_rename_state.name_value = server_tab.name.clone();
+                    self.tab_rename_state.renaming = Some(index);
+                    self.tab_rename_state.name_input_state.focus();
+                }
+            }
+            Message::TabNameChanged(s) => {
+                self.tab_rename_state.name_value = s;
+            }
+            Message::RenameTab => {
+                let name = self.tab_rename_state.name_value.trim().to_string();
+                if let Some(index) = self.tab_rename_state.renaming.take() {
+                    if let Some(server_tab) = self.server_tabs.get_mut(index) {
+                        if !name.is_empty() {
+                            server_tab.name = name;
+                        }
+                    }
+                }
+            }
+            Message::NewTab => {
+                self.conn_form_state.show_connection_form = true;
+                self.current_server_tab_index = None;
+            }
+            Message::OpenCreateKeyForm => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+
+                current_server_tab.editor_state = EditorState::Create(KeyCreateState::default());
+            }
+            Message::CreateKey => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+
+                let created = match &current_server_tab.editor_state {
+                    EditorState::Create(state) => current_server_tab
+                        .source
+                        .server_connection()
+                        .and_then(|con| {
+                            set_redis_value(con, state.key.clone(), state.value.clone())
+                        })
+                        .map(|()| true),
+                    EditorState::Empty | EditorState::Edit(_) | EditorState::Inspect(_) => {
+                        Ok(false)
+                    }
+                };
+                match created {
+                    Ok(true) => current_server_tab.editor_state = EditorState::Empty,
+                    Ok(false) => {}
+                    Err(e) => current_server_tab.command_failed("create the key", e),
+                }
+                self.refresh_keys();
+            }
+            Message::CreateKeyChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                match &mut current_server_tab.editor_state {
+                    EditorState::Empty => {}
+                    EditorState::Edit(_) => {}
+                    EditorState::Inspect(_) => {}
+                    EditorState::Create(state) => {
+                        state.key = s;
+                    }
+                }
+            }
+            Message::CreateValueChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                match &mut current_server_tab.editor_state {
+                    EditorState::Empty => {}
+                    EditorState::Edit(_) => {}
+                    EditorState::Inspect(_) => {}
+                    EditorState::Create(state) => {
+                        state.value = s;
+                    }
+                }
+            }
+            Message::NamespaceExpandToggle(indices) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let mut indices_iter = indices.iter();
+                let indices_first = indices_iter.next();
+                match indices_first {
+                    Some(first_index) => {
+                        let mut namespace_view =
+                            current_server_tab.namespaces_view.get_mut(*first_index);
+                        for i in indices_iter {
+                            namespace_view = match namespace_view {
+                                std::option::Option::Some(ns) => ns.sub_namespaces.get_mut(*i),
+                                std::option::Option::None => None,
+                            };
+                        }
+                        match namespace_view {
+                            Some(ns) => {
+                                ns.is_expanded = !ns.is_expanded;
+                            }
+                            None => {
+                                println!("failed to find namespace view for index");
+                            }
+                        }
+                    }
+                    None => {
+                        println!("no first index found in indices");
+                    }
+                }
+                current_server_tab.load_key_metadata();
+            }
+            Message::ShowMoreKeys(indices) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                if let Some(ns) =
+                    find_namespace_view(&mut current_server_tab.namespaces_view, &indices)
+                {
+                    ns.shown_keys += KEY_PAGE_SIZE;
+                }
+                current_server_tab.load_key_metadata();
+            }
+            Message::KeyListScrolled => {
+                self.follow_key_list_scroll();
+            }
+            Message::MouseDragChanged(is_dragging) => {
+                self.is_dragging = is_dragging;
+                self.follow_key_list_scroll();
+            }
+            Message::ChangePanel(panel) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.panel = panel;
+                match panel {
+                    TabPanel::Keys => {}
+                    TabPanel::Info => {
+                        if current_server_tab.info_state.sections.is_empty() {
+                            current_server_tab.refresh_info();
+                        }
+                    }
+                    TabPanel::Metrics => {
+                        if !current_server_tab.metrics_state.is_polling {
+                            current_server_tab.metrics_state.is_polling = true;
+                            current_server_tab.sample_metrics();
+                        }
+                    }
+                    TabPanel::Slowlog => {
+                        if current_server_tab.slowlog_state.entries.is_empty() {
+                            current_server_tab.fetch_slowlog();
+                            if let Some(con) = current_server_tab.source.connection() {
+                                current_server_tab.slowlog_state.load_threshold(con);
+                            }
+                        }
+                    }
+                    TabPanel::Clients => current_server_tab.refresh_clients(),
+                    TabPanel::Monitor | TabPanel::PubSub | TabPanel::Memory => {}
+                }
+            }
+            Message::RefreshInfo => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.refresh_info();
+            }
+            Message::InfoIntervalChanged(interval) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.info_state.interval = interval;
+            }
+            Message::MetricsPauseToggled => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.metrics_state.is_paused =
+                    !current_server_tab.metrics_state.is_paused;
+            }
+            Message::MetricsWindowChanged(window) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.metrics_state.window = window;
+            }
+            Message::SlowlogCountChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.slowlog_state.count_value = s;
+            }
+            Message::FetchSlowlog => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.fetch_slowlog();
+            }
+            Message::ResetSlowlog => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                match current_server_tab
+                    .source
+                    .server_connection()
+                    .and_then(reset_slowlog)
+                {
+                    Ok(()) => current_server_tab.fetch_slowlog(),
+                    Err(e) => current_server_tab.command_failed("reset the slowlog", e),
+                }
+            }
+            Message::SlowlogFilterChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.slowlog_state.filter_value = s;
+            }
+            Message::SlowlogSortChanged(column) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let slowlog_state = &mut current_server_tab.slowlog_state;
+                if slowlog_state.sort_column == column {
+                    slowlog_state.sort_descending = !slowlog_state.sort_descending;
+                } else {
+                    slowlog_state.sort_column = column;
+                    slowlog_state.sort_descending = true;
+                }
+                slowlog_state.sort_entries();
+            }
+            Message::SlowlogThresholdChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.slowlog_state.threshold_value = s;
+            }
+            Message::SaveSlowlogThreshold => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let slowlog_state = &mut current_server_tab.slowlog_state;
+                slowlog_state.status =
+                    match current_server_tab
+                        .source
+                        .server_connection()
+                        .and_then(|con| {
+                            set_config_value(
+                                con,
+                                SLOWLOG_THRESHOLD_CONFIG,
+                                &slowlog_state.threshold_value,
+                            )
+                        }) {
+                        Ok(()) => format!("{} updated", SLOWLOG_THRESHOLD_CONFIG),
+                        Err(e) => format!("Failed to set {}: {}", SLOWLOG_THRESHOLD_CONFIG, e),
+                    };
+            }
+            Message::RefreshClients => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.refresh_clients();
+            }
+            Message::ClientsFilterChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.clients_state.filter_value = s;
+            }
+            Message::ClientsSortChanged(column) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let clients_state = &mut current_server_tab.clients_state;
+                if clients_state.sort_column == column {
+                    clients_state.sort_descending = !clients_state.sort_descending;
+                } else {
+                    clients_state.sort_column = column;
+                    clients_state.sort_descending = false;
+                }
+                clients_state.sort_clients();
+            }
+            Message::ClientSelected(id) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.clients_state.selected = Some(id);
+                current_server_tab.clients_state.is_confirming_kill = false;
+            }
+            Message::KillClient => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let clients_state = &mut current_server_tab.clients_state;
+                clients_state.is_confirming_kill = clients_state.selected.is_some()
+                    && clients_state.selected != clients_state.own_id;
+            }
+            Message::ConfirmKillClient => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let clients_state = &mut current_server_tab.clients_state;
+                match clients_state.selected {
+                    Some(id) if Some(id) == clients_state.own_id => {
+                        clients_state.status =
+                            "Refusing to kill the viewer's own connection".into();
+                    }
+                    Some(id) => {
+                        clients_state.status = match current_server_tab
+                            .source
+                            .server_connection()
+                            .and_then(|con| kill_client(con, id))
+                        {
+                            Ok(0) => format!("Client {} was already disconnected", id),
+                            Ok(_) => format!("Killed client {}", id),
+                            Err(e) => format!("Failed to kill client {}: {}", id, e),
+                        };
                     }
-                    EditorState::Create(_) => {}
+                    None => {}
                 }
+                clients_state.is_confirming_kill = false;
+                current_server_tab.refresh_clients();
             }
-            Message::SelectedValueSaved => {
+            Message::CancelKillClient => {
                 let current_server_tab = self
                     .server_tabs
                     .get_mut(
@@ -308,20 +5468,116 @@ impl Application for RedisViewer {
                             .expect("failed to find current server tab index"),
                     )
                     .expect("failed to find current server tab in arena");
-                match &mut current_server_tab.editor_state {
-                    EditorState::Empty => {}
-                    EditorState::Edit(edit_state) => {
-                        set_redis_value(
-                            &mut current_server_tab.redis,
-                            edit_state.key.clone(),
-                            edit_state.value.clone(),
-                        )
-                        .expect("failed to set redis value");
+                current_server_tab.clients_state.is_confirming_kill = false;
+            }
+            Message::ToggleMonitor => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let monitor_state = &mut current_server_tab.monitor_state;
+                monitor_state.is_running = !monitor_state.is_running;
+                monitor_state.status = if monitor_state.is_running {
+                    "Connecting...".into()
+                } else {
+                    "Stopped".into()
+                };
+            }
+            Message::ToggleMonitorPause => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.monitor_state.is_paused =
+                    !current_server_tab.monitor_state.is_paused;
+            }
+            Message::ClearMonitor => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.monitor_state.entries.clear();
+            }
+            Message::MonitorCommandFilterChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.monitor_state.command_filter_value = s;
+            }
+            Message::MonitorKeyFilterChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.monitor_state.key_filter_value = s;
+            }
+            Message::MonitorClientFilterChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.monitor_state.client_filter_value = s;
+            }
+            Message::MonitorExportPathChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.monitor_state.export_path_value = s;
+            }
+            Message::ExportMonitor => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let monitor_state = &mut current_server_tab.monitor_state;
+                monitor_state.status = match monitor_state.export() {
+                    Ok(count) => format!(
+                        "Exported {} commands to {}",
+                        count, monitor_state.export_path_value
+                    ),
+                    Err(e) => format!("Failed to export: {}", e),
+                };
+            }
+            Message::MonitorEventReceived(index, event) => {
+                if let Some(server_tab) = self.server_tabs.get_mut(index) {
+                    let monitor_state = &mut server_tab.monitor_state;
+                    match event {
+                        MonitorEvent::Started => monitor_state.status = "Monitoring".into(),
+                        MonitorEvent::Entry(entry) => monitor_state.push(entry),
+                        MonitorEvent::Failed(e) => {
+                            monitor_state.is_running = false;
+                            monitor_state.status = format!("Monitor stopped: {}", e);
+                        }
                     }
-                    EditorState::Create(_) => {}
                 }
             }
-            Message::SelectedValueDeleted => {
+            Message::ToggleLiveKeys => {
                 let current_server_tab = self
                     .server_tabs
                     .get_mut(
@@ -329,85 +5585,266 @@ impl Application for RedisViewer {
                             .expect("failed to find current server tab index"),
                     )
                     .expect("failed to find current server tab in arena");
-
-                match &mut current_server_tab.editor_state {
-                    EditorState::Empty => {}
-                    EditorState::Edit(edit_state) => {
-                        delete_redis_key(&mut current_server_tab.redis, edit_state.key.clone())
-                            .expect("failed to delete key");
-                        current_server_tab.editor_state = EditorState::Empty;
+                let live_keys_state = &mut current_server_tab.live_keys_state;
+                if live_keys_state.is_enabled {
+                    live_keys_state.is_enabled = false;
+                    live_keys_state.status.clear();
+                } else {
+                    let configure_notifications = live_keys_state.configure_notifications;
+                    let flags = current_server_tab
+                        .source
+                        .server_connection()
+                        .and_then(|con| {
+                            if configure_notifications {
+                                enable_keyevent_notifications(con)
+                            } else {
+                                get_config_value(con, NOTIFY_KEYSPACE_EVENTS_CONFIG)
+                                    .map(|flags| flags.unwrap_or_default())
+                            }
+                        });
+                    live_keys_state.is_enabled = true;
+                    live_keys_state.status = match flags {
+                        Ok(flags) if flags.contains('E') => {
+                            format!("{}: {}", NOTIFY_KEYSPACE_EVENTS_CONFIG, flags)
+                        }
+                        Ok(_) => format!(
+                            "Key events are off, set {} to receive updates",
+                            NOTIFY_KEYSPACE_EVENTS_CONFIG
+                        ),
+                        Err(e) => {
+                            format!("Failed to check {}: {}", NOTIFY_KEYSPACE_EVENTS_CONFIG, e)
+                        }
+                    };
+                }
+            }
+            Message::ConfigureNotificationsToggled(b) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.live_keys_state.configure_notifications = b;
+            }
+            Message::KeyspaceEventReceived(index, event) => {
+                if let Some(server_tab) = self.server_tabs.get_mut(index) {
+                    match event {
+                        KeyspaceEvent::Subscribed => {}
+                        KeyspaceEvent::Changed { event, key } => {
+                            server_tab.apply_key_event(&event, key);
+                            server_tab.load_key_metadata();
+                        }
+                        KeyspaceEvent::Failed(e) => {
+                            let live_keys_state = &mut server_tab.live_keys_state;
+                            live_keys_state.is_enabled = false;
+                            live_keys_state.status = format!("Live updates stopped: {}", e);
+                        }
                     }
-                    EditorState::Create(_) => {}
                 }
-                self.refresh_keys();
             }
-            Message::ConnNameChanged(s) => {
-                self.conn_form_state.conn_name_value = s;
+            Message::PubSubSubscribeChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.pubsub_state.subscribe_value = s;
             }
-            Message::ConnValueChanged(s) => {
-                self.conn_form_state.conn_value = s;
+            Message::SubscribeChannel => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let pubsub_state = &mut current_server_tab.pubsub_state;
+                let channel = pubsub_state.subscribe_value.trim().to_string();
+                if !channel.is_empty() && !pubsub_state.channels.iter().any(|(c, _)| *c == channel)
+                {
+                    pubsub_state
+                        .channels
+                        .push((channel, button::State::default()));
+                    pubsub_state.subscribe_value.clear();
+                }
             }
-            Message::PortValueChanged(s) => {
-                self.conn_form_state.port_value = s;
+            Message::SubscribePattern => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let pubsub_state = &mut current_server_tab.pubsub_state;
+                let pattern = pubsub_state.subscribe_value.trim().to_string();
+                if !pattern.is_empty() && !pubsub_state.patterns.iter().any(|(p, _)| *p == pattern)
+                {
+                    pubsub_state
+                        .patterns
+                        .push((pattern, button::State::default()));
+                    pubsub_state.subscribe_value.clear();
+                }
             }
-            Message::DbValueChanged(s) => {
-                self.conn_form_state.db_value = s;
+            Message::UnsubscribeChannel(channel) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab
+                    .pubsub_state
+                    .channels
+                    .retain(|(c, _)| *c != channel);
             }
-            Message::ConnectRedis => {
-                let conn = self.conn_form_state.conn_value.clone();
-                let port: u16 = self
-                    .conn_form_state
-                    .port_value
-                    .parse()
-                    .expect("failed to parse port");
-                let db: i64 = self
-                    .conn_form_state
-                    .db_value
-                    .parse()
-                    .expect("failed to parse db");
-                let mut redis =
-                    connect_redis(&conn, port, db).expect("failed to get redis connection");
-                let keys = get_all_keys(&mut redis).expect("failed to get keys");
-                let namespaces = convert_keys_to_namespaces(&keys);
-                let namespaces_view = create_namespace_views(&namespaces);
-                let keys_scrollbar_state = KeysScrollbarState::default();
-                let mut key_buttons = Vec::<(String, button::State)>::new();
-                for key in keys.iter() {
-                    key_buttons.push((key.clone(), button::State::default()));
+            Message::UnsubscribePattern(pattern) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab
+                    .pubsub_state
+                    .patterns
+                    .retain(|(p, _)| *p != pattern);
+            }
+            Message::ClearPubSubMessages => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.pubsub_state.messages.clear();
+            }
+            Message::PublishChannelChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.pubsub_state.publish_channel_value = s;
+            }
+            Message::PublishMessageChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.pubsub_state.publish_message_value = s;
+            }
+            Message::PublishMessage => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let pubsub_state = &mut current_server_tab.pubsub_state;
+                pubsub_state.status =
+                    match current_server_tab
+                        .source
+                        .server_connection()
+                        .and_then(|con| {
+                            publish_message(
+                                con,
+                                &pubsub_state.publish_channel_value,
+                                &pubsub_state.publish_message_value,
+                            )
+                        }) {
+                        Ok(receivers) => format!("Delivered to {} subscribers", receivers),
+                        Err(e) => format!("Failed to publish: {}", e),
+                    };
+            }
+            Message::PubSubListPatternChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                current_server_tab.pubsub_state.list_pattern_value = s;
+            }
+            Message::ListPubSubChannels => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let pubsub_state = &mut current_server_tab.pubsub_state;
+                match current_server_tab
+                    .source
+                    .server_connection()
+                    .and_then(|con| get_pubsub_channels(con, &pubsub_state.list_pattern_value))
+                {
+                    Ok(channels) => pubsub_state.active_channels = channels,
+                    Err(e) => current_server_tab.command_failed("list channels", e),
                 }
-
-                let name = self.conn_form_state.conn_name_value.clone();
-
-                let server_tab = ServerTab {
-                    name,
-                    redis,
-                    keys,
-                    keys_scrollbar_state,
-                    namespaces,
-                    key_buttons,
-                    editor_state: EditorState::Empty,
-                    namespaces_view,
-                };
-                self.current_server_tab_index = Some(self.server_tabs.insert(server_tab));
-                self.tab_buttons.push((
-                    self.conn_form_state.conn_name_value.clone(),
-                    self.current_server_tab_index
-                        .expect("failed to get current server index"),
-                    button::State::default(),
-                ));
-                self.conn_form_state.show_connection_form = false;
             }
-            Message::RefreshKeys => {
-                self.refresh_keys();
+            Message::PubSubEventReceived(index, event) => {
+                if let Some(server_tab) = self.server_tabs.get_mut(index) {
+                    let pubsub_state = &mut server_tab.pubsub_state;
+                    match event {
+                        PubSubEvent::Subscribed => {
+                            pubsub_state.retry_at = None;
+                            pubsub_state.status = "Subscribed".into();
+                        }
+                        PubSubEvent::Message(message) => pubsub_state.push(message),
+                        PubSubEvent::Failed(e) => {
+                            pubsub_state.retry_at = Some(Instant::now() + PUBSUB_RETRY_DELAY);
+                            pubsub_state.status = format!(
+                                "Subscription failed: {}, retrying in {}s",
+                                e,
+                                PUBSUB_RETRY_DELAY.as_secs()
+                            );
+                        }
+                    }
+                }
             }
-            Message::ChangeTab(i) => {
-                self.current_server_tab_index = Some(i);
+            Message::MemoryPrecisionChanged(precision) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let memory_state = &mut current_server_tab.memory_state;
+                if !memory_state.is_running {
+                    memory_state.precision = precision;
+                }
             }
-            Message::NewTab => {
-                self.conn_form_state.show_connection_form = true;
-                self.current_server_tab_index = None;
+            Message::MemoryTopChanged(s) => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let memory_state = &mut current_server_tab.memory_state;
+                memory_state.top_value = s;
+                if !memory_state.is_running {
+                    memory_state.top_keys = top_keys(&memory_state.usage, memory_state.top_count());
+                }
             }
-            Message::OpenCreateKeyForm => {
+            Message::StartMemoryScan => {
                 let current_server_tab = self
                     .server_tabs
                     .get_mut(
@@ -415,10 +5852,9 @@ impl Application for RedisViewer {
                             .expect("failed to find current server tab index"),
                     )
                     .expect("failed to find current server tab in arena");
-
-                current_server_tab.editor_state = EditorState::Create(KeyCreateState::default());
+                current_server_tab.memory_state.start();
             }
-            Message::CreateKey => {
+            Message::CancelMemoryScan => {
                 let current_server_tab = self
                     .server_tabs
                     .get_mut(
@@ -426,23 +5862,75 @@ impl Application for RedisViewer {
                             .expect("failed to find current server tab index"),
                     )
                     .expect("failed to find current server tab in arena");
-
-                match &mut current_server_tab.editor_state {
-                    EditorState::Empty => {}
-                    EditorState::Edit(_) => {}
-                    EditorState::Create(state) => {
-                        set_redis_value(
-                            &mut current_server_tab.redis,
-                            state.key.clone(),
-                            state.value.clone(),
-                        )
-                        .expect("failed to set redis value");
-                        current_server_tab.editor_state = EditorState::Empty;
+                let memory_state = &mut current_server_tab.memory_state;
+                memory_state.summarize(&current_server_tab.key_grouping_state.delimiters);
+                memory_state.status = format!(
+                    "Cancelled after {} keys using {}",
+                    memory_state.usage.len(),
+                    format_bytes(memory_state.total_bytes())
+                );
+            }
+            Message::KeyScanEventReceived(index, event) => {
+                if let Some(server_tab) = self.server_tabs.get_mut(index) {
+                    match event {
+                        KeyScanEvent::Progress(keys) => {
+                            if let Some(scanned_keys) = &mut server_tab.scanned_keys {
+                                scanned_keys.extend(keys);
+                            }
+                        }
+                        KeyScanEvent::Finished => {
+                            if let Some(keys) = server_tab.scanned_keys.take() {
+                                server_tab.replace_keys(keys);
+                            }
+                        }
+                        KeyScanEvent::Failed(e) => {
+                            server_tab.scanned_keys = None;
+                            server_tab.status = format!("Failed to list keys: {}", e);
+                        }
                     }
                 }
-                self.refresh_keys();
             }
-            Message::CreateKeyChanged(s) => {
+            Message::MemoryScanEventReceived(index, event) => {
+                if let Some(server_tab) = self.server_tabs.get_mut(index) {
+                    let memory_state = &mut server_tab.memory_state;
+                    let delimiters = &server_tab.key_grouping_state.delimiters;
+                    match event {
+                        MemoryScanEvent::Progress(measured) => {
+                            memory_state.add_usage(measured);
+                            memory_state.status = format!(
+                                "Scanning... {} keys using {} so far",
+                                memory_state.usage.len(),
+                                format_bytes(memory_state.total_bytes())
+                            );
+                        }
+                        MemoryScanEvent::Finished => {
+                            memory_state.summarize(delimiters);
+                            memory_state.status = format!(
+                                "{} keys using {}",
+                                memory_state.usage.len(),
+                                format_bytes(memory_state.total_bytes())
+                            );
+                        }
+                        MemoryScanEvent::Failed(e) => {
+                            memory_state.summarize(delimiters);
+                            memory_state.status =
+                                format!("Failed after {} keys: {}", memory_state.usage.len(), e);
+                        }
+                    }
+                }
+            }
+            Message::ToggleMemoryTreemap => {
+                let current_server_tab = self
+                    .server_tabs
+                    .get_mut(
+                        self.current_server_tab_index
+                            .expect("failed to find current server tab index"),
+                    )
+                    .expect("failed to find current server tab in arena");
+                let memory_state = &mut current_server_tab.memory_state;
+                memory_state.show_treemap = !memory_state.show_treemap;
+            }
+            Message::MemoryTreemapDrillDown(name) => {
                 let current_server_tab = self
                     .server_tabs
                     .get_mut(
@@ -450,15 +5938,9 @@ impl Application for RedisViewer {
                             .expect("failed to find current server tab index"),
                     )
                     .expect("failed to find current server tab in arena");
-                match &mut current_server_tab.editor_state {
-                    EditorState::Empty => {}
-                    EditorState::Edit(_) => {}
-                    EditorState::Create(state) => {
-                        state.key = s;
-                    }
-                }
+                current_server_tab.memory_state.treemap_path.push(name);
             }
-            Message::CreateValueChanged(s) => {
+            Message::MemoryTreemapUp => {
                 let current_server_tab = self
                     .server_tabs
                     .get_mut(
@@ -466,15 +5948,9 @@ impl Application for RedisViewer {
                             .expect("failed to find current server tab index"),
                     )
                     .expect("failed to find current server tab in arena");
-                match &mut current_server_tab.editor_state {
-                    EditorState::Empty => {}
-                    EditorState::Edit(_) => {}
-                    EditorState::Create(state) => {
-                        state.value = s;
-                    }
-                }
+                current_server_tab.memory_state.treemap_path.pop();
             }
-            Message::NamespaceExpandToggle(indices) => {
+            Message::OpenMemoryKey(key) => {
                 let current_server_tab = self
                     .server_tabs
                     .get_mut(
@@ -482,95 +5958,371 @@ impl Application for RedisViewer {
                             .expect("failed to find current server tab index"),
                     )
                     .expect("failed to find current server tab in arena");
-                let mut indices_iter = indices.iter();
-                let indices_first = indices_iter.next();
-                match indices_first {
-                    Some(first_index) => {
-                        let mut namespace_view =
-                            current_server_tab.namespaces_view.get_mut(*first_index);
-                        for i in indices_iter {
-                            namespace_view = match namespace_view {
-                                std::option::Option::Some(ns) => ns.sub_namespaces.get_mut(*i),
-                                std::option::Option::None => None,
-                            };
-                        }
-                        match namespace_view {
-                            Some(ns) => {
-                                ns.is_expanded = !ns.is_expanded;
-                            }
-                            None => {
-                                println!("failed to find namespace view for index");
-                            }
-                        }
-                    }
-                    None => {
-                        println!("no first index found in indices");
+                current_server_tab.panel = TabPanel::Keys;
+                current_server_tab.open_key(key);
+            }
+            Message::PanelTick(index, panel) => {
+                let server_tab = self
+                    .server_tabs
+                    .get_mut(index)
+                    .filter(|server_tab| server_tab.health_state.is_connected());
+                if let Some(server_tab) = server_tab {
+                    match panel {
+                        TabPanel::Keys
+                        | TabPanel::Slowlog
+                        | TabPanel::Clients
+                        | TabPanel::Monitor
+                        | TabPanel::Memory => {}
+                        TabPanel::PubSub => server_tab.pubsub_state.retry_if_due(),
+                        TabPanel::Info => server_tab.refresh_info(),
+                        TabPanel::Metrics => server_tab.sample_metrics(),
                     }
                 }
             }
+            Message::HealthTick(index) => {
+                if let Some(server_tab) = self.server_tabs.get_mut(index) {
+                    server_tab.check_health();
+                }
+            }
         }
 
         Command::none()
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        let mut ticks = Vec::<(Index, TabPanel, Duration)>::new();
+        for (index, server_tab) in self.server_tabs.iter() {
+            if let Some(duration) = server_tab.info_state.interval.duration() {
+                ticks.push((index, TabPanel::Info, duration));
+            }
+            let metrics_state = &server_tab.metrics_state;
+            if metrics_state.is_polling && !metrics_state.is_paused {
+                ticks.push((index, TabPanel::Metrics, Duration::from_secs(1)));
+            }
+            if server_tab.pubsub_state.retry_at.is_some() {
+                ticks.push((index, TabPanel::PubSub, Duration::from_secs(1)));
+            }
+        }
+
+        let ticks = ticks.into_iter().map(|(index, panel, duration)| {
+            time::every(duration)
+                .with((index, panel))
+                .map(|((index, panel), _)| Message::PanelTick(index, panel))
+        });
+
+        let health_checks = self
+            .server_tabs
+            .iter()
+            .filter(|(_, server_tab)| !server_tab.is_read_only())
+            .map(|(index, server_tab)| {
+                time::every(server_tab.health_state.check_interval())
+                    .with(index)
+                    .map(|(index, _)| Message::HealthTick(index))
+            });
+
+        let monitors = self
+            .server_tabs
+            .iter()
+            .filter(|(_, server_tab)| server_tab.monitor_state.is_running)
+            .filter_map(|(index, server_tab)| Some((index, server_tab.source.settings()?)))
+            .map(|(index, settings)| {
+                Subscription::from_recipe(MonitorStream::new(settings.clone()))
+                    .with(index)
+                    .map(|(index, event)| Message::MonitorEventReceived(index, event))
+            });
+
+        let pubsubs = self
+            .server_tabs
+            .iter()
+            .filter(|(_, server_tab)| server_tab.pubsub_state.is_subscribed())
+            .filter_map(|(index, server_tab)| {
+                Some((index, server_tab, server_tab.source.settings()?))
+            })
+            .map(|(index, server_tab, settings)| {
+                let pubsub_state = &server_tab.pubsub_state;
+                Subscription::from_recipe(PubSubStream::new(
+                    settings.clone(),
+                    pubsub_state
+                        .channels
+                        .iter()
+                        .map(|(c, _)| c.clone())
+                        .collect(),
+                    pubsub_state
+                        .patterns
+                        .iter()
+                        .map(|(p, _)| p.clone())
+                        .collect(),
+                    pubsub_state.generation,
+                ))
+                .with(index)
+                .map(|(index, event)| Message::PubSubEventReceived(index, event))
+            });
+
+        let live_keys = self
+            .server_tabs
+            .iter()
+            .filter(|(_, server_tab)| server_tab.live_keys_state.is_enabled)
+            .filter_map(|(index, server_tab)| Some((index, server_tab.source.settings()?)))
+            .map(|(index, settings)| {
+                Subscription::from_recipe(KeyspaceStream::new(settings.clone()))
+                    .with(index)
+                    .map(|(index, event)| Message::KeyspaceEventReceived(index, event))
+            });
+
+        let key_scans = self.server_tabs.iter().filter_map(|(index, server_tab)| {
+            let settings = server_tab.source.settings()?;
+            server_tab.scanned_keys.as_ref().map(|_| {
+                Subscription::from_recipe(KeyScanStream::new(
+                    settings.clone(),
+                    server_tab
+                        .key_grouping_state
+                        .type_filter
+                        .type_name()
+                        .map(String::from),
+                    server_tab.key_scan_generation,
+                ))
+                .with(index)
+                .map(|(index, event)| Message::KeyScanEventReceived(index, event))
+            })
+        });
+
+        let memory_scans = self
+            .server_tabs
+            .iter()
+            .filter(|(_, server_tab)| server_tab.memory_state.is_running)
+            .filter_map(|(index, server_tab)| {
+                Some((index, server_tab, server_tab.source.settings()?))
+            })
+            .map(|(index, server_tab, settings)| {
+                Subscription::from_recipe(MemoryScanStream::new(
+                    settings.clone(),
+                    server_tab.memory_state.precision.samples(),
+                ))
+                .with(index)
+                .map(|(index, event)| Message::MemoryScanEventReceived(index, event))
+            });
+
+        let pattern_deletes = self.server_tabs.iter().filter_map(|(index, server_tab)| {
+            let settings = server_tab.source.settings()?;
+            let pattern_delete_state = &server_tab.pattern_delete_state;
+            pattern_delete_state
+                .running_patterns
+                .as_ref()
+                .map(|patterns| {
+                    Subscription::from_recipe(PatternDeleteStream::new(
+                        settings.clone(),
+                        patterns.clone(),
+                        pattern_delete_state.is_dry_run,
+                    ))
+                    .with(index)
+                    .map(|(index, event)| Message::PatternDeleteEventReceived(index, event))
+                })
+        });
+
+        // shift state for range selection in the key list
+        let modifiers = iced_native::subscription::events_with(|event, _status| match event {
+            iced_native::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            _ => None,
+        });
+
+        // iced doesn't report scrolling, so the key list window follows the
+        // wheel and scrollbar drags through raw mouse events
+        let scrolls = iced_native::subscription::events_with(|event, _status| match event {
+            iced_native::Event::Mouse(mouse::Event::WheelScrolled { .. }) => {
+                Some(Message::KeyListScrolled)
+            }
+            iced_native::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                Some(Message::MouseDragChanged(true))
+            }
+            iced_native::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                Some(Message::MouseDragChanged(false))
+            }
+            _ => None,
+        });
+        let drags = if self.is_dragging {
+            iced_native::subscription::events_with(|event, _status| match event {
+                iced_native::Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                    Some(Message::KeyListScrolled)
+                }
+                _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch(
+            ticks
+                .chain(health_checks)
+                .chain(monitors)
+                .chain(pubsubs)
+                .chain(live_keys)
+                .chain(pattern_deletes)
+                .chain(memory_scans)
+                .chain(key_scans)
+                .chain(vec![modifiers, scrolls, drags]),
+        )
+    }
+
     fn view(&mut self) -> Element<Message> {
         let content = Column::new().align_items(Align::Center).spacing(20);
 
         let content = if self.conn_form_state.show_connection_form
             || self.current_server_tab_index == None
         {
-            let connection_form = Column::new().push(
-                Row::new()
-                    .padding(10)
-                    .push(
-                        TextInput::new(
-                            &mut self.conn_form_state.conn_name_text_input_state,
-                            "Enter the nickname for your redis server here.",
-                            &self.conn_form_state.conn_name_value,
-                            Message::ConnNameChanged,
+            let connection_form = Column::new()
+                .push(
+                    Row::new()
+                        .padding(10)
+                        .push(
+                            TextInput::new(
+                                &mut self.conn_form_state.conn_name_text_input_state,
+                                "Enter the nickname for your redis server here.",
+                                &self.conn_form_state.conn_name_value,
+                                Message::ConnNameChanged,
+                            )
+                            .padding(5),
                         )
-                        .padding(5),
-                    )
-                    .push(
-                        TextInput::new(
-                            &mut self.conn_form_state.conn_text_input_state,
-                            "Enter the url for your redis server here.",
-                            &self.conn_form_state.conn_value,
-                            Message::ConnValueChanged,
+                        .push(
+                            TextInput::new(
+                                &mut self.conn_form_state.conn_text_input_state,
+                                "Enter the url for your redis server here.",
+                                &self.conn_form_state.conn_value,
+                                Message::ConnValueChanged,
+                            )
+                            .padding(5),
                         )
-                        .padding(5),
-                    )
-                    .push(
-                        TextInput::new(
-                            &mut self.conn_form_state.port_text_input_state,
-                            "Enter the port for your redis server here.",
-                            &self.conn_form_state.port_value,
-                            Message::PortValueChanged,
+                        .push(
+                            TextInput::new(
+                                &mut self.conn_form_state.port_text_input_state,
+                                "Enter the port for your redis server here.",
+                                &self.conn_form_state.port_value,
+                                Message::PortValueChanged,
+                            )
+                            .padding(5),
                         )
-                        .padding(5),
-                    )
-                    .push(
-                        TextInput::new(
-                            &mut self.conn_form_state.db_text_input_state,
-                            "Enter the db for your redis server here.",
-                            &self.conn_form_state.db_value,
-                            Message::DbValueChanged,
+                        .push(
+                            TextInput::new(
+                                &mut self.conn_form_state.db_text_input_state,
+                                "Enter the db for your redis server here.",
+                                &self.conn_form_state.db_value,
+                                Message::DbValueChanged,
+                            )
+                            .padding(5),
                         )
-                        .padding(5),
-                    )
-                    .push(
-                        Row::new().padding(10).push(
+                        .push(
+                            TextInput::new(
+                                &mut self.conn_form_state.delimiters_text_input_state,
+                                "Enter the namespace delimiters, or nothing for a flat list.",
+                                &self.conn_form_state.delimiters_value,
+                                Message::DelimitersValueChanged,
+                            )
+                            .padding(5),
+                        )
+                        .push(
+                            Row::new().padding(10).push(
+                                Button::new(
+                                    &mut self.conn_form_state.connect_button,
+                                    Text::new("Connect"),
+                                )
+                                .on_press(Message::ConnectRedis),
+                            ),
+                        ),
+                )
+                .push(
+                    Row::new()
+                        .padding(10)
+                        .spacing(10)
+                        .align_items(Align::Center)
+                        .push(Text::new("Timeouts in ms, 0 for no read or write limit:"))
+                        .push(
+                            TextInput::new(
+                                &mut self.conn_form_state.connect_timeout_text_input_state,
+                                "Connect",
+                                &self.conn_form_state.connect_timeout_value,
+                                Message::ConnectTimeoutChanged,
+                            )
+                            .padding(5),
+                        )
+                        .push(
+                            TextInput::new(
+                                &mut self.conn_form_state.read_timeout_text_input_state,
+                                "Read",
+                                &self.conn_form_state.read_timeout_value,
+                                Message::ReadTimeoutChanged,
+                            )
+                            .padding(5),
+                        )
+                        .push(
+                            TextInput::new(
+                                &mut self.conn_form_state.write_timeout_text_input_state,
+                                "Write",
+                                &self.conn_form_state.write_timeout_value,
+                                Message::WriteTimeoutChanged,
+                            )
+                            .padding(5),
+                        )
+                        .push(
                             Button::new(
-                                &mut self.conn_form_state.connect_button,
-                                Text::new("Connect"),
+                                &mut self.conn_form_state.test_button,
+                                Text::new("Test connection"),
                             )
-                            .on_press(Message::ConnectRedis),
+                            .on_press(Message::TestConnection),
                         ),
-                    ),
-            );
+                )
+                .push(
+                    Row::new()
+                        .padding(10)
+                        .push(
+                            TextInput::new(
+                                &mut self.conn_form_state.rdb_path_text_input_state,
+                                "Enter the path of an RDB file to browse it offline.",
+                                &self.conn_form_state.rdb_path_value,
+                                Message::RdbPathChanged,
+                            )
+                            .padding(5),
+                        )
+                        .push(
+                            Row::new().padding(10).push(
+                                Button::new(
+                                    &mut self.conn_form_state.open_rdb_button,
+                                    Text::new("Open RDB"),
+                                )
+                                .on_press(Message::OpenRdb),
+                            ),
+                        ),
+                )
+                .push(Text::new(&self.conn_form_state.status));
 
             content.push(connection_form)
         } else {
+            let current_server_tab_index = self.current_server_tab_index;
+            let copy_targets: Vec<CopyTarget> = self
+                .tab_buttons
+                .iter()
+                .filter(|tab_buttons| Some(tab_buttons.index) != current_server_tab_index)
+                .filter_map(|tab_buttons| {
+                    let server_tab = self.server_tabs.get(tab_buttons.index)?;
+                    if server_tab.is_read_only() {
+                        None
+                    } else {
+                        Some(CopyTarget::Tab(tab_buttons.index, server_tab.name.clone()))
+                    }
+                })
+                .chain(std::iter::once(CopyTarget::Database))
+                .collect();
+
+            let tabs = create_tabs_view(
+                &mut self.tab_buttons,
+                &mut self.tab_rename_state,
+                &self.server_tabs,
+                current_server_tab_index,
+            )
+            .push(
+                Button::new(&mut self.new_tab_button, Text::new("New")).on_press(Message::NewTab),
+            );
+
             let current_server_tab = self
                 .server_tabs
                 .get_mut(
@@ -579,42 +6331,45 @@ impl Application for RedisViewer {
                 )
                 .expect("failed to find current server tab in arena");
 
-            let tabs = self
-                .tab_buttons
-                .iter_mut()
-                .enumerate()
-                .fold(
-                    Row::new()
-                        .align_items(Align::Start)
-                        .width(Length::Fill)
-                        .height(Length::Shrink),
-                    |row, (_i, (name, index, state))| {
-                        row.push(
-                            Button::new(state, Text::new(name.clone()))
-                                .on_press(Message::ChangeTab(*index)),
-                        )
-                    },
-                )
-                .push(
-                    Button::new(&mut self.new_tab_button, Text::new("New"))
-                        .on_press(Message::NewTab),
-                );
+            let panels = self.panel_buttons.iter_mut().fold(
+                Row::new().padding(5).spacing(5),
+                |row, (panel, state)| {
+                    row.push(
+                        Button::new(state, Text::new(panel.to_string()))
+                            .on_press(Message::ChangePanel(*panel)),
+                    )
+                },
+            );
 
-            let keys = current_server_tab
-                .namespaces_view
-                .iter_mut()
-                .enumerate()
-                .fold(
-                    Scrollable::new(&mut current_server_tab.keys_scrollbar_state.state)
-                        .padding(0)
-                        .align_items(Align::Start)
-                        .width(Length::Fill)
-                        .height(Length::Fill),
-                    |scrollable, (i, ns)| {
-                        let row = create_namespace_rows(ns, [i].to_vec());
-                        scrollable.push(row)
-                    },
-                );
+            let is_read_only = current_server_tab.is_read_only();
+            let (dump_saved_at, db) = match &current_server_tab.source {
+                KeySource::Rdb(database) => (
+                    Some((database.version, database.saved_at_millis)),
+                    database.index as i64,
+                ),
+                KeySource::Server(_, settings) => (None, settings.db),
+            };
+            let selected_count = current_server_tab.key_selection_state.selected.len();
+            let keys = create_keys_view(
+                &mut current_server_tab.keys_scrollbar_state,
+                &mut current_server_tab.key_grouping_state,
+                &mut current_server_tab.key_selection_state,
+                &current_server_tab.namespaces_view,
+                &current_server_tab.key_metadata,
+                is_read_only,
+            );
+            let keys = if is_read_only {
+                keys
+            } else {
+                keys.push(create_key_tools_view(
+                    &mut current_server_tab.pattern_delete_state,
+                    &mut current_server_tab.keys_export_state,
+                    &mut current_server_tab.keys_import_state,
+                    &mut current_server_tab.keys_copy_state,
+                    copy_targets,
+                    selected_count,
+                ))
+            };
 
             let editor_column = Column::new()
                 .align_items(Align::Start)
@@ -645,6 +6400,10 @@ impl Application for RedisViewer {
                                 Button::new(&mut state.save_button_state, Text::new("Save"))
                                     .on_press(Message::SelectedValueSaved),
                             )
+                            .push(
+                                Button::new(&mut state.reload_button_state, Text::new("Reload"))
+                                    .on_press(Message::SelectedValueReloaded),
+                            )
                             .push(
                                 Button::new(&mut state.delete_button_state, Text::new("Delete"))
                                     .on_press(Message::SelectedValueDeleted),
@@ -681,35 +6440,147 @@ impl Application for RedisViewer {
                                 .on_press(Message::CreateKey),
                         ),
                     ),
+                EditorState::Inspect(state) => editor_column
+                    .push(Row::new().padding(20).push(Text::new(&state.key)))
+                    .push(
+                        state.lines.iter().fold(
+                            Scrollable::new(&mut state.scrollbar_state)
+                                .padding(20)
+                                .width(Length::Fill)
+                                .height(Length::Fill),
+                            |scrollable, line| scrollable.push(Text::new(line.as_str())),
+                        ),
+                    ),
             };
 
-            let tab_controls = Row::new()
-                .width(Length::Fill)
-                .height(Length::Shrink)
-                .push(
-                    Column::new()
-                        .padding(10)
-                        .push(Text::new(&current_server_tab.name)),
-                )
-                .push(
-                    Column::new().padding(5).push(
-                        Button::new(&mut self.keys_refresh_button_state, Text::new("Refresh"))
-                            .on_press(Message::RefreshKeys),
-                    ),
-                )
-                .push(
-                    Column::new().padding(5).push(
-                        Button::new(&mut self.create_key_button, Text::new("New Key"))
-                            .on_press(Message::OpenCreateKeyForm),
-                    ),
-                );
+            let tab_controls = Row::new().width(Length::Fill).height(Length::Shrink).push(
+                Column::new()
+                    .padding(10)
+                    .push(Text::new(&current_server_tab.name)),
+            );
+            let tab_controls = match dump_saved_at {
+                Some((version, saved_at_millis)) => {
+                    tab_controls.push(Column::new().padding(10).push(Text::new(format!(
+                        "Read-only dump (RDB version {}) saved {}",
+                        version,
+                        format_timestamp(saved_at_millis / 1000)
+                    ))))
+                }
+                None => tab_controls
+                    .push(
+                        Column::new()
+                            .padding(10)
+                            .push(Text::new(current_server_tab.health_state.summary()))
+                            .push(Text::new(current_server_tab.status.clone()).size(14))
+                            .push(
+                                Text::new(match &current_server_tab.scanned_keys {
+                                    Some(keys) => format!("Loading keys... {} so far", keys.len()),
+                                    None => String::new(),
+                                })
+                                .size(14),
+                            ),
+                    )
+                    .push(panels),
+            };
 
-            let viewer_row = Row::new()
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .padding(10)
-                .push(keys)
-                .push(editor_column);
+            let (tab_controls, viewer_row) = match current_server_tab.panel {
+                TabPanel::Keys if is_read_only => (
+                    tab_controls,
+                    Row::new()
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .padding(10)
+                        .push(keys)
+                        .push(editor_column),
+                ),
+                TabPanel::Keys => (
+                    tab_controls
+                        .push(
+                            Column::new().padding(5).push(
+                                Button::new(
+                                    &mut self.keys_refresh_button_state,
+                                    Text::new("Refresh"),
+                                )
+                                .on_press(Message::RefreshKeys),
+                            ),
+                        )
+                        .push(
+                            Column::new().padding(5).push(
+                                Button::new(&mut self.create_key_button, Text::new("New Key"))
+                                    .on_press(Message::OpenCreateKeyForm),
+                            ),
+                        )
+                        .push(create_live_keys_controls(
+                            &mut current_server_tab.live_keys_state,
+                        ))
+                        .push(create_db_switcher(
+                            &mut current_server_tab.keyspace_state,
+                            db,
+                        )),
+                    Row::new()
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .padding(10)
+                        .push(keys)
+                        .push(editor_column),
+                ),
+                TabPanel::Info => (
+                    tab_controls,
+                    Row::new()
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .padding(10)
+                        .push(create_info_view(&mut current_server_tab.info_state)),
+                ),
+                TabPanel::Metrics => (
+                    tab_controls,
+                    Row::new()
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .padding(10)
+                        .push(create_metrics_view(&mut current_server_tab.metrics_state)),
+                ),
+                TabPanel::Slowlog => (
+                    tab_controls,
+                    Row::new()
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .padding(10)
+                        .push(create_slowlog_view(&mut current_server_tab.slowlog_state)),
+                ),
+                TabPanel::Clients => (
+                    tab_controls,
+                    Row::new()
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .padding(10)
+                        .push(create_clients_view(&mut current_server_tab.clients_state)),
+                ),
+                TabPanel::Monitor => (
+                    tab_controls,
+                    Row::new()
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .padding(10)
+                        .push(create_monitor_view(&mut current_server_tab.monitor_state)),
+                ),
+                TabPanel::PubSub => (
+                    tab_controls,
+                    Row::new()
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .padding(10)
+                        .push(create_pubsub_view(&mut current_server_tab.pubsub_state)),
+                ),
+                TabPanel::Memory => (
+                    tab_controls,
+                    Row::new()
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .padding(10)
+                        .push(create_memory_view(&mut current_server_tab.memory_state)),
+                ),
+            };
 
             content.push(tabs).push(tab_controls).push(viewer_row)
         };
@@ -731,3 +6602,150 @@ impl Application for RedisViewer {
         1.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rdb::RdbEntry;
+    use std::collections::BTreeMap;
+
+    fn dump_tab(keys: &[&str]) -> ServerTab {
+        let entries: BTreeMap<String, RdbEntry> = keys
+            .iter()
+            .map(|key| {
+                let entry = RdbEntry {
+                    key_type: "string",
+                    value: RedisValue::String(String::new()),
+                    expires_at_millis: None,
+                };
+                (key.to_string(), entry)
+            })
+            .collect();
+        let database = RdbDatabase {
+            index: 0,
+            entries,
+            saved_at_millis: 0,
+            version: 9,
+        };
+        let keys = database.keys(None);
+        ServerTab::new("dump".into(), KeySource::Rdb(database), keys, ":".into())
+    }
+
+    fn expand(views: &mut [NamespaceView], path: &[&str]) {
+        let view = views
+            .iter_mut()
+            .find(|view| view.namespace == path[0])
+            .expect("missing namespace");
+        view.is_expanded = true;
+        if path.len() > 1 {
+            expand(&mut view.sub_namespaces, &path[1..]);
+        }
+    }
+
+    fn tree_lines(views: &[NamespaceView], skip: usize, limit: usize) -> Vec<String> {
+        let mut skip = skip;
+        let mut rows = Vec::new();
+        collect_tree_rows(views, &[], &mut skip, limit, &mut rows);
+        rows.into_iter()
+            .map(|row| match row {
+                KeyTreeRow::Namespace {
+                    name,
+                    key_count,
+                    is_expanded,
+                    ..
+                } => format!(
+                    "{} {}{}",
+                    name,
+                    key_count,
+                    if is_expanded { " open" } else { "" }
+                ),
+                KeyTreeRow::Key { depth, key } => format!("{}{}", "  ".repeat(depth), key),
+                KeyTreeRow::MoreKeys { remaining, .. } => format!("{} more", remaining),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn added_keys_keep_namespaces_expanded() {
+        let mut tab = dump_tab(&["plain", "user:1", "user:2"]);
+        expand(&mut tab.namespaces_view, &[""]);
+        expand(&mut tab.namespaces_view, &["user", "1"]);
+        tab.key_selection_state.selected.insert("user:1".into());
+
+        tab.add_key("user:10".into());
+        tab.add_key("user:10".into());
+        tab.add_key("zone".into());
+        assert_eq!(
+            tree_lines(&tab.namespaces_view, 0, usize::MAX),
+            vec![
+                " 2 open",
+                "  plain",
+                "  zone",
+                "user 3 open",
+                "1 1 open",
+                "    user:1",
+                "2 1",
+                "10 1",
+            ]
+        );
+        assert!(tab.key_selection_state.selected.contains("user:1"));
+        assert_eq!(tab.keys.len(), 5);
+    }
+
+    #[test]
+    fn removed_keys_drop_empty_namespaces_only() {
+        let mut tab = dump_tab(&["plain", "user:1", "user:2", "user:3"]);
+        expand(&mut tab.namespaces_view, &["user", "1"]);
+        tab.key_selection_state.selected = ["user:1", "user:3"]
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+
+        tab.remove_key("user:3");
+        tab.remove_key("user:3");
+        tab.remove_key("missing");
+        assert_eq!(
+            tree_lines(&tab.namespaces_view, 0, usize::MAX),
+            vec![" 1", "user 2 open", "1 1 open", "    user:1", "2 1"]
+        );
+        assert_eq!(
+            tab.key_selection_state.selected,
+            ["user:1".to_string()].iter().cloned().collect()
+        );
+
+        tab.remove_key("user:1");
+        tab.remove_key("user:2");
+        assert_eq!(tree_lines(&tab.namespaces_view, 0, usize::MAX), vec![" 1"]);
+        assert!(tab.key_selection_state.selected.is_empty());
+    }
+
+    #[test]
+    fn tree_rows_window_and_page_long_key_lists() {
+        let mut tab = dump_tab(&["a", "b", "c", "d"]);
+        expand(&mut tab.namespaces_view, &[""]);
+        tab.namespaces_view[0].shown_keys = 3;
+        assert_eq!(count_tree_rows(&tab.namespaces_view), 5);
+        assert_eq!(
+            tree_lines(&tab.namespaces_view, 0, usize::MAX),
+            vec![" 4 open", "  a", "  b", "  c", "1 more"]
+        );
+        assert_eq!(tree_lines(&tab.namespaces_view, 2, 2), vec!["  b", "  c"]);
+        assert_eq!(tree_lines(&tab.namespaces_view, 4, 10), vec!["1 more"]);
+    }
+
+    #[test]
+    fn sorted_keys_ignore_duplicates_in_either_order() {
+        let mut keys = Vec::new();
+        for key in &["k2", "k10", "k1", "k2"] {
+            insert_sorted_key(&mut keys, key, false);
+        }
+        assert_eq!(keys, vec!["k1", "k2", "k10"]);
+
+        let mut keys = Vec::new();
+        for key in &["k2", "k10", "k1"] {
+            insert_sorted_key(&mut keys, key, true);
+        }
+        assert_eq!(keys, vec!["k10", "k2", "k1"]);
+        assert!(!insert_sorted_key(&mut keys, "k2", true));
+    }
+}
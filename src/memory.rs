@@ -0,0 +1,207 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::redislogic::redislogic::{
+    convert_keys_to_namespaces, natural_cmp, KeyMemory, RedisNamespace,
+};
+
+/// The memory of a namespace, counting every key below it.
+#[derive(Debug, Clone)]
+pub struct NamespaceMemory {
+    /// The parts of the prefix from the top level down. Keys without any
+    /// delimiter are gathered under a single empty part.
+    pub path: Vec<String>,
+    pub bytes: u64,
+    pub keys: u64,
+}
+
+/// Sums the measured keys up the namespace tree they form. Rows come in
+/// tree order, with the biggest namespace first at every level.
+pub fn aggregate_namespaces(usage: &[KeyMemory], delimiters: &str) -> Vec<NamespaceMemory> {
+    let keys: Vec<String> = usage
+        .iter()
+        .map(|key_memory| key_memory.key.clone())
+        .collect();
+    let bytes: HashMap<&str, u64> = usage
+        .iter()
+        .map(|key_memory| (key_memory.key.as_str(), key_memory.bytes))
+        .collect();
+    let namespaces = convert_keys_to_namespaces(&keys, delimiters);
+
+    let mut rows = Vec::<NamespaceMemory>::new();
+    collect_namespace_rows(&namespaces, &[], &bytes, &mut rows);
+    rows
+}
+
+fn namespace_total(namespace: &RedisNamespace, bytes: &HashMap<&str, u64>) -> (u64, u64) {
+    let own = namespace
+        .keys
+        .iter()
+        .map(|key| bytes.get(key.as_str()).copied().unwrap_or(0))
+        .sum::<u64>();
+    namespace.sub_namespaces.values().fold(
+        (own, namespace.keys.len() as u64),
+        |(total_bytes, total_keys), sub_namespace| {
+            let (sub_bytes, sub_keys) = namespace_total(sub_namespace, bytes);
+            (total_bytes + sub_bytes, total_keys + sub_keys)
+        },
+    )
+}
+
+fn collect_namespace_rows(
+    namespaces: &HashMap<String, RedisNamespace>,
+    path: &[String],
+    bytes: &HashMap<&str, u64>,
+    rows: &mut Vec<NamespaceMemory>,
+) {
+    // a namespace without sub namespaces is just the last part of its key
+    let mut children: Vec<(&RedisNamespace, u64, u64)> = namespaces
+        .values()
+        .filter(|namespace| {
+            !namespace.sub_namespaces.is_empty()
+                || (path.is_empty() && namespace.name.is_empty() && !namespace.keys.is_empty())
+        })
+        .map(|namespace| {
+            let (total_bytes, total_keys) = namespace_total(namespace, bytes);
+            (namespace, total_bytes, total_keys)
+        })
+        .collect();
+    children.sort_by(|(a, a_bytes, _), (b, b_bytes, _)| {
+        b_bytes
+            .cmp(a_bytes)
+            .then_with(|| natural_cmp(&a.name, &b.name))
+    });
+
+    for (namespace, total_bytes, total_keys) in children {
+        let mut child_path = path.to_vec();
+        child_path.push(namespace.name.clone());
+        rows.push(NamespaceMemory {
+            path: child_path.clone(),
+            bytes: total_bytes,
+            keys: total_keys,
+        });
+        collect_namespace_rows(&namespace.sub_namespaces, &child_path, bytes, rows);
+    }
+}
+
+/// The `count` biggest keys, biggest first.
+pub fn top_keys(usage: &[KeyMemory], count: usize) -> Vec<KeyMemory> {
+    let mut keys = usage.to_vec();
+    keys.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then_with(|| natural_cmp(&a.key, &b.key))
+    });
+    keys.truncate(count);
+    keys
+}
+
+/// A namespace or key in the memory tree drawn as a treemap.
+#[derive(Debug, Clone)]
+pub struct MemoryTreeNode {
+    pub name: String,
+    /// Set for nodes that are a key rather than a namespace.
+    pub key: Option<String>,
+    pub bytes: u64,
+    pub keys: u64,
+    /// Bytes per key type below the node.
+    pub type_bytes: BTreeMap<String, u64>,
+    /// Biggest first.
+    pub children: Vec<MemoryTreeNode>,
+}
+
+impl MemoryTreeNode {
+    /// The type holding most of the bytes below the node.
+    pub fn dominant_type(&self) -> &str {
+        self.type_bytes
+            .iter()
+            .max_by_key(|(_, bytes)| **bytes)
+            .map_or("", |(key_type, _)| key_type.as_str())
+    }
+}
+
+/// Builds the namespace tree of the measured keys. Keys sit next to the
+/// namespaces of their level, keys without a delimiter at the top.
+pub fn build_memory_tree(usage: &[KeyMemory], delimiters: &str) -> Vec<MemoryTreeNode> {
+    let keys: Vec<String> = usage
+        .iter()
+        .map(|key_memory| key_memory.key.clone())
+        .collect();
+    let measured: HashMap<&str, &KeyMemory> = usage
+        .iter()
+        .map(|key_memory| (key_memory.key.as_str(), key_memory))
+        .collect();
+    let namespaces = convert_keys_to_namespaces(&keys, delimiters);
+
+    let mut nodes = Vec::<MemoryTreeNode>::new();
+    for namespace in namespaces.values() {
+        push_tree_nodes(namespace, &measured, &mut nodes);
+    }
+    sort_tree_nodes(&mut nodes);
+    nodes
+}
+
+fn push_tree_nodes(
+    namespace: &RedisNamespace,
+    measured: &HashMap<&str, &KeyMemory>,
+    nodes: &mut Vec<MemoryTreeNode>,
+) {
+    let key_nodes = namespace
+        .keys
+        .iter()
+        .filter_map(|key| measured.get(key.as_str()))
+        .map(|key_memory| MemoryTreeNode {
+            name: key_memory.key.clone(),
+            key: Some(key_memory.key.clone()),
+            bytes: key_memory.bytes,
+            keys: 1,
+            type_bytes: std::iter::once((key_memory.key_type.clone(), key_memory.bytes)).collect(),
+            children: Vec::<MemoryTreeNode>::new(),
+        });
+    // namespaces without sub namespaces are the last part of their keys
+    if namespace.sub_namespaces.is_empty() {
+        nodes.extend(key_nodes);
+        return;
+    }
+
+    let mut children: Vec<MemoryTreeNode> = key_nodes.collect();
+    for sub_namespace in namespace.sub_namespaces.values() {
+        push_tree_nodes(sub_namespace, measured, &mut children);
+    }
+    sort_tree_nodes(&mut children);
+
+    let mut type_bytes = BTreeMap::<String, u64>::new();
+    for child in children.iter() {
+        for (key_type, bytes) in child.type_bytes.iter() {
+            *type_bytes.entry(key_type.clone()).or_insert(0) += bytes;
+        }
+    }
+    nodes.push(MemoryTreeNode {
+        name: namespace.name.clone(),
+        key: None,
+        bytes: children.iter().map(|child| child.bytes).sum(),
+        keys: children.iter().map(|child| child.keys).sum(),
+        type_bytes,
+        children,
+    });
+}
+
+fn sort_tree_nodes(nodes: &mut [MemoryTreeNode]) {
+    nodes.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then_with(|| natural_cmp(&a.name, &b.name))
+    });
+}
+
+/// The nodes below the namespace at `path`, or `None` once it is gone.
+pub fn memory_tree_children<'a>(
+    nodes: &'a [MemoryTreeNode],
+    path: &[String],
+) -> Option<&'a [MemoryTreeNode]> {
+    path.iter().try_fold(nodes, |nodes, name| {
+        nodes
+            .iter()
+            .find(|node| node.key.is_none() && node.name == *name)
+            .map(|node| &node.children[..])
+    })
+}
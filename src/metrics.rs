@@ -0,0 +1,302 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use iced::{
+    canvas::{self, Cursor, Frame, Geometry, Path, Stroke, Text},
+    Color, HorizontalAlignment, Point, Rectangle,
+};
+
+use crate::format::format_bytes;
+use crate::redislogic::redislogic::InfoSection;
+
+/// Samples older than the longest chart window are dropped.
+const MAX_HISTORY: Duration = Duration::from_secs(3600);
+
+const CHART_BACKGROUND: Color = Color::from_rgb(
+    0x2E as f32 / 255.0,
+    0x33 as f32 / 255.0,
+    0x38 as f32 / 255.0,
+);
+
+const CHART_LINE: Color = Color::from_rgb(
+    0x6F as f32 / 255.0,
+    0xFF as f32 / 255.0,
+    0xE9 as f32 / 255.0,
+);
+
+const TITLE_HEIGHT: f32 = 24.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    OpsPerSec,
+    ConnectedClients,
+    UsedMemory,
+    HitRatio,
+    EvictedKeys,
+    ExpiredKeys,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 6] = [
+        Metric::OpsPerSec,
+        Metric::ConnectedClients,
+        Metric::UsedMemory,
+        Metric::HitRatio,
+        Metric::EvictedKeys,
+        Metric::ExpiredKeys,
+    ];
+
+    pub fn format_value(&self, value: f64) -> String {
+        match self {
+            Metric::UsedMemory => format_bytes(value as u64),
+            Metric::HitRatio => format!("{:.1}%", value * 100.0),
+            Metric::EvictedKeys | Metric::ExpiredKeys => format!("{:.1}/s", value),
+            Metric::OpsPerSec | Metric::ConnectedClients => format!("{:.0}", value),
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Metric::OpsPerSec => "Ops/sec",
+            Metric::ConnectedClients => "Connected clients",
+            Metric::UsedMemory => "Used memory",
+            Metric::HitRatio => "Hit ratio",
+            Metric::EvictedKeys => "Evicted keys",
+            Metric::ExpiredKeys => "Expired keys",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartWindow {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+}
+
+impl ChartWindow {
+    pub const ALL: [ChartWindow; 4] = [
+        ChartWindow::OneMinute,
+        ChartWindow::FiveMinutes,
+        ChartWindow::FifteenMinutes,
+        ChartWindow::OneHour,
+    ];
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            ChartWindow::OneMinute => Duration::from_secs(60),
+            ChartWindow::FiveMinutes => Duration::from_secs(300),
+            ChartWindow::FifteenMinutes => Duration::from_secs(900),
+            ChartWindow::OneHour => MAX_HISTORY,
+        }
+    }
+}
+
+impl fmt::Display for ChartWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ChartWindow::OneMinute => "Last 1m",
+            ChartWindow::FiveMinutes => "Last 5m",
+            ChartWindow::FifteenMinutes => "Last 15m",
+            ChartWindow::OneHour => "Last 1h",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The raw INFO figures needed to chart every [`Metric`]. Counters such as
+/// `keyspace_hits` are kept cumulative and turned into rates when plotted.
+pub struct MetricSample {
+    taken_at: Instant,
+    ops_per_sec: f64,
+    connected_clients: f64,
+    used_memory: f64,
+    keyspace_hits: f64,
+    keyspace_misses: f64,
+    evicted_keys: f64,
+    expired_keys: f64,
+}
+
+impl MetricSample {
+    pub fn from_info(sections: &[InfoSection]) -> Self {
+        let field = |name: &str| -> f64 {
+            sections
+                .iter()
+                .flat_map(|section| section.entries.iter())
+                .find(|(field, _)| field == name)
+                .and_then(|(_, value)| value.parse().ok())
+                .unwrap_or(0.0)
+        };
+
+        MetricSample {
+            taken_at: Instant::now(),
+            ops_per_sec: field("instantaneous_ops_per_sec"),
+            connected_clients: field("connected_clients"),
+            used_memory: field("used_memory"),
+            keyspace_hits: field("keyspace_hits"),
+            keyspace_misses: field("keyspace_misses"),
+            evicted_keys: field("evicted_keys"),
+            expired_keys: field("expired_keys"),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MetricsHistory {
+    samples: VecDeque<MetricSample>,
+}
+
+impl MetricsHistory {
+    pub fn push(&mut self, sample: MetricSample) {
+        let now = sample.taken_at;
+        self.samples.push_back(sample);
+        while let Some(oldest) = self.samples.front() {
+            if now.duration_since(oldest.taken_at) > MAX_HISTORY {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Returns `(seconds ago, value)` pairs for `metric` inside `window`,
+    /// oldest first. Ages are relative to the newest sample so a paused
+    /// history stays frozen in place.
+    pub fn points(&self, metric: Metric, window: Duration) -> Vec<(f32, f64)> {
+        let now = match self.samples.back() {
+            Some(sample) => sample.taken_at,
+            None => return Vec::new(),
+        };
+
+        let mut points = Vec::<(f32, f64)>::new();
+        let mut previous: Option<&MetricSample> = None;
+        for sample in self.samples.iter() {
+            let age = now.duration_since(sample.taken_at);
+            let value = match metric {
+                Metric::OpsPerSec => Some(sample.ops_per_sec),
+                Metric::ConnectedClients => Some(sample.connected_clients),
+                Metric::UsedMemory => Some(sample.used_memory),
+                Metric::HitRatio => previous.and_then(|previous| {
+                    let hits = sample.keyspace_hits - previous.keyspace_hits;
+                    let misses = sample.keyspace_misses - previous.keyspace_misses;
+                    if hits + misses > 0.0 {
+                        Some(hits / (hits + misses))
+                    } else {
+                        None
+                    }
+                }),
+                Metric::EvictedKeys => {
+                    previous.map(|previous| rate(previous, sample, |s| s.evicted_keys))
+                }
+                Metric::ExpiredKeys => {
+                    previous.map(|previous| rate(previous, sample, |s| s.expired_keys))
+                }
+            };
+            previous = Some(sample);
+
+            if age > window {
+                continue;
+            }
+            if let Some(value) = value {
+                points.push((age.as_secs_f32(), value));
+            }
+        }
+        points
+    }
+}
+
+fn rate(previous: &MetricSample, sample: &MetricSample, counter: fn(&MetricSample) -> f64) -> f64 {
+    let elapsed = sample
+        .taken_at
+        .duration_since(previous.taken_at)
+        .as_secs_f64();
+    if elapsed > 0.0 {
+        (counter(sample) - counter(previous)).max(0.0) / elapsed
+    } else {
+        0.0
+    }
+}
+
+pub struct MetricChart {
+    metric: Metric,
+    points: Vec<(f32, f64)>,
+    window: Duration,
+}
+
+impl MetricChart {
+    pub fn new(history: &MetricsHistory, metric: Metric, window: ChartWindow) -> Self {
+        let window = window.duration();
+        MetricChart {
+            metric,
+            points: history.points(metric, window),
+            window,
+        }
+    }
+}
+
+impl<Message> canvas::Program<Message> for MetricChart {
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, frame.size(), CHART_BACKGROUND);
+
+        let width = frame.width();
+        let plot_height = frame.height() - TITLE_HEIGHT - 4.0;
+        let max = match self.metric {
+            Metric::HitRatio => 1.0,
+            _ => {
+                let max = self.points.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+                if max > 0.0 {
+                    max * 1.1
+                } else {
+                    1.0
+                }
+            }
+        };
+        let window = self.window.as_secs_f32();
+
+        let line = Path::new(|builder| {
+            for (i, (age, value)) in self.points.iter().enumerate() {
+                let point = Point::new(
+                    width * (1.0 - age / window),
+                    TITLE_HEIGHT + plot_height * (1.0 - (*value / max) as f32),
+                );
+                if i == 0 {
+                    builder.move_to(point);
+                } else {
+                    builder.line_to(point);
+                }
+            }
+        });
+        frame.stroke(
+            &line,
+            Stroke::default().with_color(CHART_LINE).with_width(2.0),
+        );
+
+        let current = match self.points.last() {
+            Some((_, value)) => self.metric.format_value(*value),
+            None => "-".into(),
+        };
+        frame.fill_text(Text {
+            content: format!("{}: {}", self.metric, current),
+            position: Point::new(8.0, 4.0),
+            color: Color::WHITE,
+            ..Text::default()
+        });
+        frame.fill_text(Text {
+            content: self.metric.format_value(max),
+            position: Point::new(width - 8.0, 4.0),
+            color: Color::WHITE,
+            horizontal_alignment: HorizontalAlignment::Right,
+            ..Text::default()
+        });
+
+        vec![frame.into_geometry()]
+    }
+}
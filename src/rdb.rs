@@ -0,0 +1,821 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::redislogic::redislogic::{KeyMetadata, RedisValue};
+
+const OPCODE_SLOT_INFO: u8 = 0xf4;
+const OPCODE_FUNCTION2: u8 = 0xf5;
+const OPCODE_MODULE_AUX: u8 = 0xf7;
+const OPCODE_IDLE: u8 = 0xf8;
+const OPCODE_FREQ: u8 = 0xf9;
+const OPCODE_AUX: u8 = 0xfa;
+const OPCODE_RESIZEDB: u8 = 0xfb;
+const OPCODE_EXPIRETIME_MS: u8 = 0xfc;
+const OPCODE_EXPIRETIME: u8 = 0xfd;
+const OPCODE_SELECTDB: u8 = 0xfe;
+const OPCODE_EOF: u8 = 0xff;
+
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+const TYPE_ZSET: u8 = 3;
+const TYPE_HASH: u8 = 4;
+const TYPE_ZSET_2: u8 = 5;
+const TYPE_MODULE_2: u8 = 7;
+const TYPE_HASH_ZIPMAP: u8 = 9;
+const TYPE_LIST_ZIPLIST: u8 = 10;
+const TYPE_SET_INTSET: u8 = 11;
+const TYPE_ZSET_ZIPLIST: u8 = 12;
+const TYPE_HASH_ZIPLIST: u8 = 13;
+const TYPE_LIST_QUICKLIST: u8 = 14;
+const TYPE_STREAM_LISTPACKS: u8 = 15;
+const TYPE_HASH_LISTPACK: u8 = 16;
+const TYPE_ZSET_LISTPACK: u8 = 17;
+const TYPE_LIST_QUICKLIST_2: u8 = 18;
+const TYPE_STREAM_LISTPACKS_2: u8 = 19;
+const TYPE_SET_LISTPACK: u8 = 20;
+const TYPE_STREAM_LISTPACKS_3: u8 = 21;
+
+const QUICKLIST_NODE_PLAIN: u64 = 1;
+
+const MODULE_OPCODE_EOF: u64 = 0;
+const MODULE_OPCODE_SINT: u64 = 1;
+const MODULE_OPCODE_UINT: u64 = 2;
+const MODULE_OPCODE_FLOAT: u64 = 3;
+const MODULE_OPCODE_DOUBLE: u64 = 4;
+const MODULE_OPCODE_STRING: u64 = 5;
+
+fn invalid_data<T>(message: String) -> io::Result<T> {
+    Err(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+fn to_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// A key read from a dump, with the value converted to what the viewer shows
+/// for a live server.
+#[derive(Debug, Clone)]
+pub struct RdbEntry {
+    pub key_type: &'static str,
+    pub value: RedisValue,
+    /// Absolute expiry as a unix timestamp in milliseconds.
+    pub expires_at_millis: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RdbDatabase {
+    pub index: u64,
+    pub entries: BTreeMap<String, RdbEntry>,
+    /// When the dump was taken, so TTLs read as they were at that moment.
+    pub saved_at_millis: i64,
+    /// The RDB format version of the dump.
+    pub version: u32,
+}
+
+impl RdbDatabase {
+    /// The keys of the database, optionally only those of one type.
+    pub fn keys(&self, type_name: Option<&str>) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| match type_name {
+                Some(type_name) => entry.key_type == type_name,
+                None => true,
+            })
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    pub fn value(&self, key: &str) -> RedisValue {
+        self.entries
+            .get(key)
+            .map_or(RedisValue::Null, |entry| entry.value.clone())
+    }
+
+    /// Metadata in the shape of `get_key_metadata`, with TTLs relative to the
+    /// time the dump was saved.
+    pub fn metadata(&self, keys: &[String]) -> Vec<KeyMetadata> {
+        keys.iter()
+            .map(|key| match self.entries.get(key) {
+                Some(entry) => KeyMetadata {
+                    key_type: entry.key_type.to_string(),
+                    ttl_millis: entry
+                        .expires_at_millis
+                        .map_or(-1, |expires_at| (expires_at - self.saved_at_millis).max(0)),
+                },
+                None => KeyMetadata {
+                    key_type: "none".to_string(),
+                    ttl_millis: -2,
+                },
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RdbFile {
+    /// Every database that holds at least one key, by index.
+    pub databases: Vec<RdbDatabase>,
+}
+
+impl RdbFile {
+    pub fn read(path: &str) -> io::Result<Self> {
+        let data = fs::read(path)?;
+        let modified_millis = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or_else(|_| SystemTime::now())
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_millis() as i64);
+        parse_rdb(&data, modified_millis)
+    }
+}
+
+/// Parses a whole dump. `fallback_saved_at_millis` stands in for the save
+/// time when the dump doesn't record one in its `ctime` aux field.
+pub fn parse_rdb(data: &[u8], fallback_saved_at_millis: i64) -> io::Result<RdbFile> {
+    let mut reader = RdbReader { data, pos: 0 };
+    let magic = reader.bytes(9)?;
+    if &magic[..5] != b"REDIS" {
+        return invalid_data("not an RDB file".to_string());
+    }
+    let version = std::str::from_utf8(&magic[5..])
+        .ok()
+        .and_then(|version| version.parse::<u32>().ok())
+        .map_or_else(|| invalid_data("invalid RDB version".to_string()), Ok)?;
+
+    let mut databases = BTreeMap::<u64, BTreeMap<String, RdbEntry>>::new();
+    let mut saved_at_millis = None;
+    let mut db = 0;
+    let mut expires_at_millis = None;
+    loop {
+        let opcode = reader.u8()?;
+        match opcode {
+            OPCODE_EOF => break,
+            OPCODE_SELECTDB => db = reader.length()?,
+            OPCODE_RESIZEDB => {
+                reader.length()?;
+                reader.length()?;
+            }
+            OPCODE_AUX => {
+                let name = reader.string()?;
+                let value = reader.string()?;
+                if name == b"ctime" {
+                    saved_at_millis = std::str::from_utf8(&value)
+                        .ok()
+                        .and_then(|seconds| seconds.parse::<i64>().ok())
+                        .map(|seconds| seconds * 1000);
+                }
+            }
+            OPCODE_EXPIRETIME_MS => expires_at_millis = Some(reader.i64_le()?),
+            OPCODE_EXPIRETIME => expires_at_millis = Some(reader.u32_le()? as i64 * 1000),
+            OPCODE_IDLE => {
+                reader.length()?;
+            }
+            OPCODE_FREQ => {
+                reader.u8()?;
+            }
+            OPCODE_MODULE_AUX => {
+                reader.length()?;
+                reader.length()?;
+                reader.length()?;
+                reader.skip_module_value()?;
+            }
+            OPCODE_FUNCTION2 => {
+                reader.string()?;
+            }
+            OPCODE_SLOT_INFO => {
+                reader.length()?;
+                reader.length()?;
+                reader.length()?;
+            }
+            value_type => {
+                let key = to_text(&reader.string()?);
+                let (key_type, value) = reader
+                    .value(value_type)
+                    .or_else(|e| invalid_data(format!("key \"{}\": {}", key, e)))?;
+                databases.entry(db).or_default().insert(
+                    key,
+                    RdbEntry {
+                        key_type,
+                        value,
+                        expires_at_millis: expires_at_millis.take(),
+                    },
+                );
+            }
+        }
+    }
+
+    let saved_at_millis = saved_at_millis.unwrap_or(fallback_saved_at_millis);
+    Ok(RdbFile {
+        databases: databases
+            .into_iter()
+            .map(|(index, entries)| RdbDatabase {
+                index,
+                entries,
+                saved_at_millis,
+                version,
+            })
+            .collect(),
+    })
+}
+
+/// A single entry of a ziplist or listpack, which store small integers
+/// without their decimal text.
+enum PackedEntry {
+    Bytes(Vec<u8>),
+    Int(i64),
+}
+
+impl PackedEntry {
+    fn into_string(self) -> String {
+        match self {
+            PackedEntry::Bytes(bytes) => to_text(&bytes),
+            PackedEntry::Int(value) => value.to_string(),
+        }
+    }
+}
+
+fn pairs(entries: Vec<String>) -> Vec<(String, String)> {
+    let mut entries = entries.into_iter();
+    let mut pairs = Vec::<(String, String)>::new();
+    while let (Some(first), Some(second)) = (entries.next(), entries.next()) {
+        pairs.push((first, second));
+    }
+    pairs
+}
+
+/// Formats a score the way ZRANGE WITHSCORES prints it.
+fn format_score(score: f64) -> String {
+    if score.is_nan() {
+        "nan".to_string()
+    } else {
+        score.to_string()
+    }
+}
+
+struct RdbReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> RdbReader<'a> {
+    fn bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len());
+        match end {
+            Some(end) => {
+                let bytes = &self.data[self.pos..end];
+                self.pos = end;
+                Ok(bytes)
+            }
+            None => invalid_data(format!("unexpected end of data at byte {}", self.pos)),
+        }
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32_le(&mut self) -> io::Result<u32> {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(self.bytes(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn i64_le(&mut self) -> io::Result<i64> {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(self.bytes(8)?);
+        Ok(i64::from_le_bytes(buf))
+    }
+
+    fn f64_le(&mut self) -> io::Result<f64> {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(self.bytes(8)?);
+        Ok(f64::from_le_bytes(buf))
+    }
+
+    /// Reads a length, or the special encoding of an integer or compressed
+    /// string as `Err` with the encoding number.
+    fn length_or_encoding(&mut self) -> io::Result<Result<u64, u8>> {
+        let first = self.u8()?;
+        Ok(match first >> 6 {
+            0 => Ok((first & 0x3f) as u64),
+            1 => Ok((((first & 0x3f) as u64) << 8) | self.u8()? as u64),
+            2 if first == 0x80 => {
+                let mut buf = [0; 4];
+                buf.copy_from_slice(self.bytes(4)?);
+                Ok(u32::from_be_bytes(buf) as u64)
+            }
+            2 if first == 0x81 => {
+                let mut buf = [0; 8];
+                buf.copy_from_slice(self.bytes(8)?);
+                Ok(u64::from_be_bytes(buf))
+            }
+            2 => return invalid_data(format!("invalid length prefix {:#x}", first)),
+            _ => Err(first & 0x3f),
+        })
+    }
+
+    fn length(&mut self) -> io::Result<u64> {
+        match self.length_or_encoding()? {
+            Ok(len) => Ok(len),
+            Err(encoding) => {
+                invalid_data(format!("expected a length, found encoding {}", encoding))
+            }
+        }
+    }
+
+    fn len(&mut self) -> io::Result<usize> {
+        Ok(self.length()? as usize)
+    }
+
+    fn string(&mut self) -> io::Result<Vec<u8>> {
+        match self.length_or_encoding()? {
+            Ok(len) => Ok(self.bytes(len as usize)?.to_vec()),
+            Err(0) => Ok((self.u8()? as i8).to_string().into_bytes()),
+            Err(1) => {
+                let bytes = self.bytes(2)?;
+                Ok(i16::from_le_bytes([bytes[0], bytes[1]])
+                    .to_string()
+                    .into_bytes())
+            }
+            Err(2) => {
+                let value = self.u32_le()? as i32;
+                Ok(value.to_string().into_bytes())
+            }
+            Err(3) => {
+                let compressed_len = self.len()?;
+                let len = self.len()?;
+                lzf_decompress(self.bytes(compressed_len)?, len)
+            }
+            Err(encoding) => invalid_data(format!("unknown string encoding {}", encoding)),
+        }
+    }
+
+    /// The scores of the original zset encoding, written as text with a one
+    /// byte length that doubles as a marker for nan and the infinities.
+    fn text_score(&mut self) -> io::Result<String> {
+        Ok(match self.u8()? {
+            253 => "nan".to_string(),
+            254 => "inf".to_string(),
+            255 => "-inf".to_string(),
+            len => to_text(self.bytes(len as usize)?),
+        })
+    }
+
+    fn strings(&mut self, count: usize) -> io::Result<Vec<String>> {
+        (0..count).map(|_| Ok(to_text(&self.string()?))).collect()
+    }
+
+    fn value(&mut self, value_type: u8) -> io::Result<(&'static str, RedisValue)> {
+        Ok(match value_type {
+            TYPE_STRING => ("string", RedisValue::String(to_text(&self.string()?))),
+            TYPE_LIST => {
+                let len = self.len()?;
+                ("list", RedisValue::List(self.strings(len)?))
+            }
+            TYPE_SET => {
+                let len = self.len()?;
+                ("set", RedisValue::Set(self.strings(len)?))
+            }
+            TYPE_ZSET | TYPE_ZSET_2 => {
+                let len = self.len()?;
+                // lengths come from the file, so they don't size allocations
+                let mut members = Vec::<(String, String)>::new();
+                for _ in 0..len {
+                    let member = to_text(&self.string()?);
+                    let score = if value_type == TYPE_ZSET {
+                        self.text_score()?
+                    } else {
+                        format_score(self.f64_le()?)
+                    };
+                    members.push((member, score));
+                }
+                ("zset", RedisValue::ZSet(members))
+            }
+            TYPE_HASH => {
+                let len = self.len()?;
+                let count = match len.checked_mul(2) {
+                    Some(count) => count,
+                    None => return invalid_data(format!("invalid hash length {}", len)),
+                };
+                let fields = self.strings(count)?;
+                (
+                    "hash",
+                    RedisValue::Hash(pairs(fields).into_iter().collect()),
+                )
+            }
+            TYPE_HASH_ZIPMAP => (
+                "hash",
+                RedisValue::Hash(parse_zipmap(&self.string()?)?.into_iter().collect()),
+            ),
+            TYPE_LIST_ZIPLIST => ("list", RedisValue::List(parse_ziplist(&self.string()?)?)),
+            TYPE_SET_INTSET => ("set", RedisValue::Set(parse_intset(&self.string()?)?)),
+            TYPE_SET_LISTPACK => ("set", RedisValue::Set(parse_listpack(&self.string()?)?)),
+            TYPE_ZSET_ZIPLIST => (
+                "zset",
+                RedisValue::ZSet(pairs(parse_ziplist(&self.string()?)?)),
+            ),
+            TYPE_ZSET_LISTPACK => (
+                "zset",
+                RedisValue::ZSet(pairs(parse_listpack(&self.string()?)?)),
+            ),
+            TYPE_HASH_ZIPLIST => (
+                "hash",
+                RedisValue::Hash(pairs(parse_ziplist(&self.string()?)?).into_iter().collect()),
+            ),
+            TYPE_HASH_LISTPACK => (
+                "hash",
+                RedisValue::Hash(
+                    pairs(parse_listpack(&self.string()?)?)
+                        .into_iter()
+                        .collect(),
+                ),
+            ),
+            TYPE_LIST_QUICKLIST => {
+                let nodes = self.len()?;
+                let mut items = Vec::<String>::new();
+                for _ in 0..nodes {
+                    items.extend(parse_ziplist(&self.string()?)?);
+                }
+                ("list", RedisValue::List(items))
+            }
+            TYPE_LIST_QUICKLIST_2 => {
+                let nodes = self.len()?;
+                let mut items = Vec::<String>::new();
+                for _ in 0..nodes {
+                    let container = self.length()?;
+                    let node = self.string()?;
+                    if container == QUICKLIST_NODE_PLAIN {
+                        items.push(to_text(&node));
+                    } else {
+                        items.extend(parse_listpack(&node)?);
+                    }
+                }
+                ("list", RedisValue::List(items))
+            }
+            TYPE_STREAM_LISTPACKS | TYPE_STREAM_LISTPACKS_2 | TYPE_STREAM_LISTPACKS_3 => {
+                self.skip_stream(value_type)?;
+                ("stream", RedisValue::Null)
+            }
+            TYPE_MODULE_2 => {
+                self.length()?;
+                self.skip_module_value()?;
+                ("module", RedisValue::Null)
+            }
+            value_type => return invalid_data(format!("unsupported value type {}", value_type)),
+        })
+    }
+
+    /// Streams aren't shown by the viewer, but their layout still has to be
+    /// walked to reach the keys after them.
+    fn skip_stream(&mut self, value_type: u8) -> io::Result<()> {
+        let newer = value_type >= TYPE_STREAM_LISTPACKS_2;
+        for _ in 0..self.length()? {
+            self.string()?;
+            self.string()?;
+        }
+        // Length and last id, then the first id, max deleted id and entries
+        // added of newer versions.
+        let header_lengths = if newer { 8 } else { 3 };
+        for _ in 0..header_lengths {
+            self.length()?;
+        }
+        for _ in 0..self.length()? {
+            self.string()?;
+            self.length()?;
+            self.length()?;
+            if newer {
+                self.length()?;
+            }
+            for _ in 0..self.length()? {
+                self.bytes(16 + 8)?;
+                self.length()?;
+            }
+            for _ in 0..self.length()? {
+                self.string()?;
+                self.bytes(8)?;
+                if value_type >= TYPE_STREAM_LISTPACKS_3 {
+                    self.bytes(8)?;
+                }
+                for _ in 0..self.length()? {
+                    self.bytes(16)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_module_value(&mut self) -> io::Result<()> {
+        loop {
+            match self.length()? {
+                MODULE_OPCODE_EOF => return Ok(()),
+                MODULE_OPCODE_SINT | MODULE_OPCODE_UINT => {
+                    self.length()?;
+                }
+                MODULE_OPCODE_FLOAT => {
+                    self.bytes(4)?;
+                }
+                MODULE_OPCODE_DOUBLE => {
+                    self.bytes(8)?;
+                }
+                MODULE_OPCODE_STRING => {
+                    self.string()?;
+                }
+                opcode => return invalid_data(format!("unknown module opcode {}", opcode)),
+            }
+        }
+    }
+}
+
+fn lzf_decompress(input: &[u8], len: usize) -> io::Result<Vec<u8>> {
+    let corrupt = || io::Error::new(io::ErrorKind::InvalidData, "corrupt LZF data");
+    let mut output = Vec::<u8>::new();
+    let mut i = 0;
+    while i < input.len() {
+        let ctrl = input[i] as usize;
+        i += 1;
+        if ctrl < 32 {
+            match input.get(i..i + ctrl + 1) {
+                Some(literal) => output.extend_from_slice(literal),
+                None => return Err(corrupt()),
+            }
+            i += ctrl + 1;
+        } else {
+            let mut run = ctrl >> 5;
+            if run == 7 {
+                run += *input.get(i).ok_or_else(corrupt)? as usize;
+                i += 1;
+            }
+            let offset = ((ctrl & 0x1f) << 8) + *input.get(i).ok_or_else(corrupt)? as usize + 1;
+            i += 1;
+            if offset > output.len() {
+                return Err(corrupt());
+            }
+            let start = output.len() - offset;
+            for j in 0..run + 2 {
+                output.push(output[start + j]);
+            }
+        }
+    }
+    if output.len() != len {
+        return Err(corrupt());
+    }
+    Ok(output)
+}
+
+fn parse_intset(data: &[u8]) -> io::Result<Vec<String>> {
+    let mut reader = RdbReader { data, pos: 0 };
+    let width = reader.u32_le()? as usize;
+    let count = reader.u32_le()? as usize;
+    (0..count)
+        .map(|_| {
+            let bytes = reader.bytes(width)?;
+            Ok(match width {
+                2 => i16::from_le_bytes([bytes[0], bytes[1]]) as i64,
+                4 => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i64,
+                8 => {
+                    let mut buf = [0; 8];
+                    buf.copy_from_slice(bytes);
+                    i64::from_le_bytes(buf)
+                }
+                _ => return invalid_data(format!("invalid intset encoding {}", width)),
+            }
+            .to_string())
+        })
+        .collect()
+}
+
+/// Reads `len` bytes as a little-endian signed integer.
+fn read_int(reader: &mut RdbReader<'_>, len: usize) -> io::Result<i64> {
+    let bytes = reader.bytes(len)?;
+    let mut buf = [0; 8];
+    buf[..len].copy_from_slice(bytes);
+    let shift = 64 - 8 * len as u32;
+    Ok((i64::from_le_bytes(buf) << shift) >> shift)
+}
+
+fn parse_ziplist(data: &[u8]) -> io::Result<Vec<String>> {
+    let mut reader = RdbReader { data, pos: 10 };
+    let mut entries = Vec::<String>::new();
+    loop {
+        // Each entry starts with the length of the previous one, which takes
+        // four more bytes after a 0xfe marker.
+        match reader.u8()? {
+            0xff => return Ok(entries),
+            0xfe => {
+                reader.bytes(4)?;
+            }
+            _ => {}
+        }
+        let encoding = reader.u8()?;
+        let entry = match encoding >> 6 {
+            0 => PackedEntry::Bytes(reader.bytes((encoding & 0x3f) as usize)?.to_vec()),
+            1 => {
+                let len = (((encoding & 0x3f) as usize) << 8) | reader.u8()? as usize;
+                PackedEntry::Bytes(reader.bytes(len)?.to_vec())
+            }
+            2 => {
+                let bytes = reader.bytes(4)?;
+                let len = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+                PackedEntry::Bytes(reader.bytes(len)?.to_vec())
+            }
+            _ => PackedEntry::Int(match encoding {
+                0xc0 => read_int(&mut reader, 2)?,
+                0xd0 => read_int(&mut reader, 4)?,
+                0xe0 => read_int(&mut reader, 8)?,
+                0xf0 => read_int(&mut reader, 3)?,
+                0xfe => read_int(&mut reader, 1)?,
+                0xf1..=0xfd => (encoding & 0x0f) as i64 - 1,
+                _ => return invalid_data(format!("invalid ziplist encoding {:#x}", encoding)),
+            }),
+        };
+        entries.push(entry.into_string());
+    }
+}
+
+fn parse_listpack(data: &[u8]) -> io::Result<Vec<String>> {
+    let mut reader = RdbReader { data, pos: 6 };
+    let mut entries = Vec::<String>::new();
+    loop {
+        let start = reader.pos;
+        let encoding = reader.u8()?;
+        let entry = match encoding {
+            0xff => return Ok(entries),
+            0x00..=0x7f => PackedEntry::Int(encoding as i64),
+            0x80..=0xbf => PackedEntry::Bytes(reader.bytes((encoding & 0x3f) as usize)?.to_vec()),
+            0xc0..=0xdf => {
+                let value = (((encoding & 0x1f) as i64) << 8) | reader.u8()? as i64;
+                PackedEntry::Int(if value >= 1 << 12 {
+                    value - (1 << 13)
+                } else {
+                    value
+                })
+            }
+            0xe0..=0xef => {
+                let len = (((encoding & 0x0f) as usize) << 8) | reader.u8()? as usize;
+                PackedEntry::Bytes(reader.bytes(len)?.to_vec())
+            }
+            0xf0 => {
+                let len = reader.u32_le()? as usize;
+                PackedEntry::Bytes(reader.bytes(len)?.to_vec())
+            }
+            0xf1 => PackedEntry::Int(read_int(&mut reader, 2)?),
+            0xf2 => PackedEntry::Int(read_int(&mut reader, 3)?),
+            0xf3 => PackedEntry::Int(read_int(&mut reader, 4)?),
+            0xf4 => PackedEntry::Int(read_int(&mut reader, 8)?),
+            _ => return invalid_data(format!("invalid listpack encoding {:#x}", encoding)),
+        };
+        let backlen = match reader.pos - start {
+            0..=127 => 1,
+            128..=16382 => 2,
+            16383..=2097150 => 3,
+            2097151..=268435454 => 4,
+            _ => 5,
+        };
+        reader.bytes(backlen)?;
+        entries.push(entry.into_string());
+    }
+}
+
+/// The hash encoding of dumps written before Redis 2.6.
+fn parse_zipmap(data: &[u8]) -> io::Result<Vec<(String, String)>> {
+    let mut reader = RdbReader { data, pos: 1 };
+    let read_len = |reader: &mut RdbReader<'_>| -> io::Result<Option<usize>> {
+        Ok(match reader.u8()? {
+            0xff => None,
+            0xfe => Some(reader.u32_le()? as usize),
+            len => Some(len as usize),
+        })
+    };
+    let mut fields = Vec::<(String, String)>::new();
+    while let Some(field_len) = read_len(&mut reader)? {
+        let field = to_text(reader.bytes(field_len)?);
+        let value_len = read_len(&mut reader)?
+            .map_or_else(|| invalid_data("truncated zipmap".to_string()), Ok)?;
+        let free = reader.u8()? as usize;
+        let value = to_text(reader.bytes(value_len)?);
+        reader.bytes(free)?;
+        fields.push((field, value));
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intset_reads_each_width() {
+        let data = [2, 0, 0, 0, 3, 0, 0, 0, 0x01, 0x00, 0xff, 0xff, 0x00, 0x80];
+        assert_eq!(parse_intset(&data).unwrap(), vec!["1", "-1", "-32768"]);
+        let data = [8, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80];
+        assert_eq!(parse_intset(&data).unwrap(), vec![i64::MIN.to_string()]);
+        assert!(parse_intset(&[3, 0, 0, 0, 1, 0, 0, 0, 1, 2, 3]).is_err());
+        assert!(parse_intset(&[4, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn ziplist_reads_strings_and_integers() {
+        let mut data = vec![0; 10];
+        data.extend(&[0x00, 0x02, b'a', b'b']);
+        data.extend(&[0x04, 0xfd]);
+        data.extend(&[0x02, 0xc0, 0xfe, 0xff]);
+        data.extend(&[0xfe, 0, 0, 0, 0, 0xf0, 0x00, 0x00, 0x80]);
+        data.push(0xff);
+        assert_eq!(
+            parse_ziplist(&data).unwrap(),
+            vec!["ab", "12", "-2", "-8388608"]
+        );
+        data.pop();
+        assert!(parse_ziplist(&data).is_err());
+    }
+
+    #[test]
+    fn listpack_reads_strings_and_integers() {
+        let mut data = vec![0; 6];
+        data.extend(&[0x05, 1]);
+        data.extend(&[0x83, b'a', b'b', b'c', 4]);
+        data.extend(&[0xdf, 0xff, 2]);
+        data.extend(&[0xf1, 0x2c, 0x01, 3]);
+        data.push(0xff);
+        assert_eq!(
+            parse_listpack(&data).unwrap(),
+            vec!["5", "abc", "-1", "300"]
+        );
+        assert!(parse_listpack(&[0, 0, 0, 0, 0, 0, 0x85, b'a', 1]).is_err());
+    }
+
+    #[test]
+    fn lzf_expands_literals_and_back_references() {
+        let data = [0x02, b'a', b'b', b'c', 0x60, 0x02];
+        assert_eq!(lzf_decompress(&data, 8).unwrap(), b"abcabcab".to_vec());
+        assert!(lzf_decompress(&data, 9).is_err());
+        assert!(lzf_decompress(&[0x20, 0x05], 3).is_err());
+        assert!(lzf_decompress(&[0x05, b'a'], 6).is_err());
+    }
+
+    fn push_string(data: &mut Vec<u8>, bytes: &[u8]) {
+        data.push(bytes.len() as u8);
+        data.extend(bytes);
+    }
+
+    #[test]
+    fn parse_rdb_reads_databases_values_and_expiry() {
+        let mut data = b"REDIS0009".to_vec();
+        data.push(OPCODE_AUX);
+        push_string(&mut data, b"redis-ver");
+        push_string(&mut data, b"6.2.6");
+        data.push(OPCODE_AUX);
+        push_string(&mut data, b"ctime");
+        push_string(&mut data, b"1700000000");
+        data.extend(&[OPCODE_SELECTDB, 0, OPCODE_RESIZEDB, 2, 1]);
+        data.push(OPCODE_EXPIRETIME_MS);
+        data.extend(&1_700_000_060_000i64.to_le_bytes());
+        data.push(TYPE_STRING);
+        push_string(&mut data, b"greeting");
+        push_string(&mut data, b"hello");
+        data.push(TYPE_STRING);
+        push_string(&mut data, b"counter");
+        data.extend(&[0xc0, 42]);
+        data.extend(&[OPCODE_SELECTDB, 3, OPCODE_RESIZEDB, 1, 0]);
+        data.push(TYPE_LIST);
+        push_string(&mut data, b"queue");
+        data.push(2);
+        push_string(&mut data, b"a");
+        push_string(&mut data, b"b");
+        data.push(OPCODE_EOF);
+        data.extend(&[0; 8]);
+
+        let rdb_file = parse_rdb(&data, 0).unwrap();
+        assert_eq!(rdb_file.databases.len(), 2);
+        let db0 = &rdb_file.databases[0];
+        assert_eq!((db0.index, db0.version), (0, 9));
+        assert_eq!(db0.saved_at_millis, 1_700_000_000_000);
+        assert_eq!(db0.keys(None), vec!["counter", "greeting"]);
+        let greeting = &db0.entries["greeting"];
+        assert_eq!(greeting.key_type, "string");
+        assert!(matches!(&greeting.value, RedisValue::String(value) if value == "hello"));
+        assert_eq!(greeting.expires_at_millis, Some(1_700_000_060_000));
+        let counter = &db0.entries["counter"];
+        assert!(matches!(&counter.value, RedisValue::String(value) if value == "42"));
+        assert_eq!(counter.expires_at_millis, None);
+        let metadata = db0.metadata(&["greeting".to_string()]);
+        assert_eq!(metadata[0].ttl_millis, 60_000);
+
+        let db3 = &rdb_file.databases[1];
+        assert_eq!(db3.index, 3);
+        assert_eq!(db3.keys(Some("list")), vec!["queue"]);
+        assert!(
+            matches!(&db3.entries["queue"].value, RedisValue::List(items) if items == &["a", "b"])
+        );
+
+        assert!(parse_rdb(b"REDIX0009\xff", 0).is_err());
+        assert!(parse_rdb(&data[..data.len() - 10], 0).is_err());
+    }
+}
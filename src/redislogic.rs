@@ -1,30 +1,214 @@
 pub(crate) mod redislogic {
     use redis::{Commands, Connection, ConnectionAddr};
+    use std::cmp::Ordering;
     use std::collections::HashMap;
+    use std::fmt;
+    use std::time::{Duration, Instant};
 
-    pub fn connect_redis(address: &str, port: u16, db: i64) -> redis::RedisResult<Connection> {
+    use crate::format::format_duration;
+
+    /// Name every connection reports through CLIENT SETNAME, so other admins
+    /// can tell the viewer apart in CLIENT LIST.
+    pub const CLIENT_NAME: &str = "icy-redis-viewer";
+
+    const UNLINK_BATCH_SIZE: usize = 1000;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct ConnectionSettings {
+        pub address: String,
+        pub port: u16,
+        pub db: i64,
+        pub connect_timeout: Duration,
+        /// How long a reply may take, without a limit when `None`.
+        pub read_timeout: Option<Duration>,
+        pub write_timeout: Option<Duration>,
+    }
+
+    fn open_connection(settings: &ConnectionSettings, db: i64) -> redis::RedisResult<Connection> {
         let client = redis::Client::open(redis::ConnectionInfo {
-            addr: Box::new(ConnectionAddr::Tcp(address.to_string(), port)),
+            addr: Box::new(ConnectionAddr::Tcp(settings.address.clone(), settings.port)),
             db,
             username: None,
             passwd: None,
         })?;
-        client.get_connection()
+        let con = client.get_connection_with_timeout(settings.connect_timeout)?;
+        con.set_read_timeout(settings.read_timeout)?;
+        con.set_write_timeout(settings.write_timeout)?;
+        Ok(con)
+    }
+
+    pub fn connect_redis(settings: &ConnectionSettings) -> redis::RedisResult<Connection> {
+        let mut con = open_connection(settings, settings.db)?;
+        redis::cmd("CLIENT")
+            .arg("SETNAME")
+            .arg(CLIENT_NAME)
+            .query::<()>(&mut con)?;
+        Ok(con)
+    }
+
+    pub fn ping(con: &mut redis::Connection) -> redis::RedisResult<()> {
+        redis::cmd("PING").query::<String>(con)?;
+        Ok(())
+    }
+
+    /// What "Test connection" found out about a server.
+    pub struct ConnectionCheck {
+        /// The round trip of a PING.
+        pub latency: Duration,
+        pub requires_password: bool,
+        pub version: Option<String>,
+        pub role: Option<String>,
+    }
+
+    impl fmt::Display for ConnectionCheck {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "Reached in {:.1} ms",
+                self.latency.as_secs_f64() * 1000.0
+            )?;
+            if let Some(version) = &self.version {
+                write!(f, ", Redis {}", version)?;
+            }
+            if let Some(role) = &self.role {
+                write!(f, ", {}", role)?;
+            }
+            if self.requires_password {
+                write!(f, ", requires a password")
+            } else {
+                write!(f, ", no password required")
+            }
+        }
+    }
+
+    /// Connects without opening a tab. The connection starts on db 0 so that a
+    /// server asking for a password isn't mistaken for a bad db.
+    pub fn test_connection(settings: &ConnectionSettings) -> redis::RedisResult<ConnectionCheck> {
+        let mut con = open_connection(settings, 0)?;
+        let started = Instant::now();
+        let pong = ping(&mut con);
+        let mut check = ConnectionCheck {
+            latency: started.elapsed(),
+            requires_password: false,
+            version: None,
+            role: None,
+        };
+        match pong {
+            Ok(()) => {}
+            Err(e) if e.code() == Some("NOAUTH") => {
+                check.requires_password = true;
+                return Ok(check);
+            }
+            Err(e) => return Err(e),
+        }
+
+        if settings.db != 0 {
+            select_db(&mut con, settings.db)?;
+        }
+        let info: String = redis::cmd("INFO").query(&mut con)?;
+        for section in parse_info(&info) {
+            for (field, value) in section.entries {
+                match field.as_str() {
+                    "redis_version" => check.version = Some(value),
+                    "role" => check.role = Some(value),
+                    _ => {}
+                }
+            }
+        }
+        Ok(check)
     }
 
-    pub fn get_all_keys(redis: &mut redis::Connection) -> redis::RedisResult<Vec<String>> {
-        let all_keys: Vec<String> = redis.keys("*")?;
-        Ok(all_keys)
+    pub fn scan_keys(
+        con: &mut redis::Connection,
+        pattern: &str,
+    ) -> redis::RedisResult<Vec<String>> {
+        let keys: Vec<String> = con.scan_match(pattern)?.collect();
+        Ok(keys)
+    }
+
+    /// Fetches the type and remaining time to live of each key in a single
+    /// pipeline.
+    pub fn get_key_metadata(
+        con: &mut redis::Connection,
+        keys: &[String],
+    ) -> redis::RedisResult<Vec<KeyMetadata>> {
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.cmd("TYPE").arg(key).cmd("PTTL").arg(key);
+        }
+        let replies: Vec<redis::Value> = pipe.query(con)?;
+
+        let mut metadata = Vec::<KeyMetadata>::new();
+        for reply in replies.chunks(2) {
+            metadata.push(KeyMetadata {
+                key_type: redis::from_redis_value(&reply[0])?,
+                ttl_millis: redis::from_redis_value(&reply[1])?,
+            });
+        }
+        Ok(metadata)
+    }
+
+    /// Measures each key with MEMORY USAGE, passing `samples` on as its
+    /// SAMPLES option, and counts the elements of collections. Keys that are
+    /// gone by the time they are measured are left out.
+    pub fn get_key_memory(
+        con: &mut redis::Connection,
+        keys: &[String],
+        samples: usize,
+    ) -> redis::RedisResult<Vec<KeyMemory>> {
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.cmd("TYPE")
+                .arg(key)
+                .cmd("MEMORY")
+                .arg("USAGE")
+                .arg(key)
+                .arg("SAMPLES")
+                .arg(samples);
+        }
+        let replies: Vec<redis::Value> = pipe.query(con)?;
+
+        let mut measured = Vec::<KeyMemory>::new();
+        for (key, reply) in keys.iter().zip(replies.chunks(2)) {
+            let bytes: Option<u64> = redis::from_redis_value(&reply[1])?;
+            if let Some(bytes) = bytes {
+                measured.push(KeyMemory {
+                    key: key.clone(),
+                    key_type: redis::from_redis_value(&reply[0])?,
+                    bytes,
+                    elements: None,
+                });
+            }
+        }
+
+        let mut pipe = redis::pipe();
+        let mut counted = Vec::<usize>::new();
+        for (i, key_memory) in measured.iter().enumerate() {
+            let command = match key_memory.key_type.as_str() {
+                "list" => "LLEN",
+                "set" => "SCARD",
+                "zset" => "ZCARD",
+                "hash" => "HLEN",
+                "stream" => "XLEN",
+                _ => continue,
+            };
+            pipe.cmd(command).arg(&key_memory.key);
+            counted.push(i);
+        }
+        if !counted.is_empty() {
+            let counts: Vec<u64> = pipe.query(con)?;
+            for (i, count) in counted.into_iter().zip(counts) {
+                measured[i].elements = Some(count);
+            }
+        }
+        Ok(measured)
     }
 
     pub fn get_redis_value(
         redis: &mut redis::Connection,
         key: &str,
     ) -> redis::RedisResult<RedisValue> {
-        let key_type: String = redis::cmd("TYPE")
-            .arg(key)
-            .query(redis)
-            .expect("failed to get key type");
+        let key_type: String = redis::cmd("TYPE").arg(key).query(redis)?;
         let value: Result<RedisValue, redis::RedisError> = match key_type.as_str() {
             "string" => {
                 let v: String = redis.get(key)?;
@@ -60,12 +244,574 @@ pub(crate) mod redislogic {
         Ok(())
     }
 
+    /// Unlinks keys in batches so a large selection never turns into one huge
+    /// command. Returns how many keys were removed.
+    pub fn unlink_keys(con: &mut redis::Connection, keys: &[String]) -> redis::RedisResult<u64> {
+        let mut removed = 0;
+        for batch in keys.chunks(UNLINK_BATCH_SIZE) {
+            removed += redis::cmd("UNLINK").arg(batch).query::<u64>(con)?;
+        }
+        Ok(removed)
+    }
+
+    /// Copies keys with DUMP and RESTORE, keeping their remaining TTL. Every
+    /// key gets its own outcome so one failure doesn't stop the rest.
+    pub fn copy_keys(
+        source: &mut redis::Connection,
+        target: &mut redis::Connection,
+        keys: &[String],
+        replace: bool,
+    ) -> Vec<(String, Result<(), String>)> {
+        keys.iter()
+            .map(|key| {
+                let result = copy_key(source, target, key, replace).map_err(|e| e.to_string());
+                (key.clone(), result)
+            })
+            .collect()
+    }
+
+    fn copy_key(
+        source: &mut redis::Connection,
+        target: &mut redis::Connection,
+        key: &str,
+        replace: bool,
+    ) -> redis::RedisResult<()> {
+        let (ttl_millis, payload): (i64, Option<Vec<u8>>) = redis::pipe()
+            .cmd("PTTL")
+            .arg(key)
+            .cmd("DUMP")
+            .arg(key)
+            .query(source)?;
+        let payload = match payload {
+            Some(payload) => payload,
+            None => {
+                return Err(redis::RedisError::from((
+                    redis::ErrorKind::ResponseError,
+                    "key no longer exists",
+                )))
+            }
+        };
+
+        let mut restore = redis::cmd("RESTORE");
+        restore.arg(key).arg(ttl_millis.max(0)).arg(payload);
+        if replace {
+            restore.arg("REPLACE");
+        }
+        restore.query(target)
+    }
+
     pub fn delete_redis_key(con: &mut redis::Connection, key: String) -> redis::RedisResult<()> {
         let _: () = con.del(key)?;
         Ok(())
     }
 
-    pub fn convert_keys_to_namespaces(keys: &Vec<String>) -> HashMap<String, RedisNamespace> {
+    pub fn get_server_info(con: &mut redis::Connection) -> redis::RedisResult<Vec<InfoSection>> {
+        let info: String = redis::cmd("INFO").query(con)?;
+        Ok(parse_info(&info))
+    }
+
+    /// Lists the databases holding keys, as INFO keyspace reports them.
+    pub fn get_keyspace(con: &mut redis::Connection) -> redis::RedisResult<Vec<KeyspaceDb>> {
+        let info: String = redis::cmd("INFO").arg("keyspace").query(con)?;
+        Ok(info.lines().filter_map(parse_keyspace_line).collect())
+    }
+
+    /// Parses a line like `db0:keys=12,expires=3,avg_ttl=5000`.
+    pub fn parse_keyspace_line(line: &str) -> Option<KeyspaceDb> {
+        let (name, fields) = line.trim().split_once(':')?;
+        let mut keyspace_db = KeyspaceDb {
+            db: name.strip_prefix("db")?.parse().ok()?,
+            keys: 0,
+            expires: 0,
+            avg_ttl_millis: 0,
+        };
+        for field in fields.split(',') {
+            let (field, value) = field.split_once('=')?;
+            // newer servers add fields, like subexpiry in redis 7.4
+            let target = match field {
+                "keys" => &mut keyspace_db.keys,
+                "expires" => &mut keyspace_db.expires,
+                "avg_ttl" => &mut keyspace_db.avg_ttl_millis,
+                _ => continue,
+            };
+            *target = value.parse().ok()?;
+        }
+        Some(keyspace_db)
+    }
+
+    pub fn select_db(con: &mut redis::Connection, db: i64) -> redis::RedisResult<()> {
+        redis::cmd("SELECT").arg(db).query(con)
+    }
+
+    pub fn parse_info(info: &str) -> Vec<InfoSection> {
+        let mut sections = Vec::<InfoSection>::new();
+
+        for line in info.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('#') {
+                sections.push(InfoSection {
+                    name: name.trim().to_lowercase(),
+                    entries: Vec::<(String, String)>::new(),
+                });
+            } else if let Some((field, value)) = line.split_once(':') {
+                if let Some(section) = sections.last_mut() {
+                    section.entries.push((field.into(), value.into()));
+                }
+            }
+        }
+        sections
+    }
+
+    pub fn get_slowlog(
+        con: &mut redis::Connection,
+        count: usize,
+    ) -> redis::RedisResult<Vec<SlowlogEntry>> {
+        let entries: Vec<Vec<redis::Value>> =
+            redis::cmd("SLOWLOG").arg("GET").arg(count).query(con)?;
+
+        entries
+            .iter()
+            .map(|entry| {
+                let field = |i: usize| entry.get(i).unwrap_or(&redis::Value::Nil);
+                Ok(SlowlogEntry {
+                    id: redis::from_redis_value(field(0))?,
+                    timestamp: redis::from_redis_value(field(1))?,
+                    duration_micros: redis::from_redis_value(field(2))?,
+                    command: redis::from_redis_value(field(3))?,
+                    // client address and name are only reported since redis 4.0
+                    client_addr: redis::from_redis_value(field(4)).unwrap_or_default(),
+                    client_name: redis::from_redis_value(field(5)).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn reset_slowlog(con: &mut redis::Connection) -> redis::RedisResult<()> {
+        redis::cmd("SLOWLOG").arg("RESET").query(con)
+    }
+
+    pub fn get_config_value(
+        con: &mut redis::Connection,
+        parameter: &str,
+    ) -> redis::RedisResult<Option<String>> {
+        let values: Vec<String> = redis::cmd("CONFIG").arg("GET").arg(parameter).query(con)?;
+        Ok(values.into_iter().nth(1))
+    }
+
+    pub fn set_config_value(
+        con: &mut redis::Connection,
+        parameter: &str,
+        value: &str,
+    ) -> redis::RedisResult<()> {
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg(parameter)
+            .arg(value)
+            .query(con)
+    }
+
+    /// Turns on key event notifications for every command class while keeping
+    /// the flags that were already configured. Returns the new flags.
+    pub fn enable_keyevent_notifications(
+        con: &mut redis::Connection,
+    ) -> redis::RedisResult<String> {
+        let mut flags = get_config_value(con, "notify-keyspace-events")?.unwrap_or_default();
+        for flag in &['E', 'A'] {
+            if !flags.contains(*flag) {
+                flags.push(*flag);
+            }
+        }
+        set_config_value(con, "notify-keyspace-events", &flags)?;
+        Ok(flags)
+    }
+
+    pub fn get_client_list(con: &mut redis::Connection) -> redis::RedisResult<Vec<ClientInfo>> {
+        let list: String = redis::cmd("CLIENT").arg("LIST").query(con)?;
+        Ok(parse_client_list(&list))
+    }
+
+    pub fn parse_client_list(list: &str) -> Vec<ClientInfo> {
+        list.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let fields: HashMap<&str, &str> = line
+                    .split_whitespace()
+                    .filter_map(|field| field.split_once('='))
+                    .collect();
+                let text = |name: &str| fields.get(name).copied().unwrap_or("").to_string();
+                let number = |name: &str| {
+                    fields
+                        .get(name)
+                        .and_then(|value| value.parse().ok())
+                        .unwrap_or(0)
+                };
+
+                ClientInfo {
+                    id: number("id"),
+                    addr: text("addr"),
+                    name: text("name"),
+                    age: number("age"),
+                    idle: number("idle"),
+                    db: number("db") as i64,
+                    cmd: text("cmd"),
+                    // tot-mem was added in redis 6.0, older servers only report the output buffer
+                    memory: fields
+                        .get("tot-mem")
+                        .or_else(|| fields.get("omem"))
+                        .and_then(|value| value.parse().ok())
+                        .unwrap_or(0),
+                }
+            })
+            .collect()
+    }
+
+    pub fn get_client_id(con: &mut redis::Connection) -> redis::RedisResult<u64> {
+        redis::cmd("CLIENT").arg("ID").query(con)
+    }
+
+    /// Returns the number of connections that were closed, which is zero when
+    /// the client already went away.
+    pub fn kill_client(con: &mut redis::Connection, id: u64) -> redis::RedisResult<u64> {
+        redis::cmd("CLIENT")
+            .arg("KILL")
+            .arg("ID")
+            .arg(id)
+            .query(con)
+    }
+
+    /// Returns the number of subscribers that received the message.
+    pub fn publish_message(
+        con: &mut redis::Connection,
+        channel: &str,
+        message: &str,
+    ) -> redis::RedisResult<u64> {
+        con.publish(channel, message)
+    }
+
+    /// Lists active channels matching `pattern` along with their subscriber
+    /// counts from PUBSUB NUMSUB.
+    pub fn get_pubsub_channels(
+        con: &mut redis::Connection,
+        pattern: &str,
+    ) -> redis::RedisResult<Vec<(String, u64)>> {
+        let mut channels: Vec<String> = redis::cmd("PUBSUB")
+            .arg("CHANNELS")
+            .arg(pattern)
+            .query(con)?;
+        if channels.is_empty() {
+            return Ok(Vec::new());
+        }
+        channels.sort();
+
+        redis::cmd("PUBSUB")
+            .arg("NUMSUB")
+            .arg(&channels[..])
+            .query(con)
+    }
+
+    /// Parses a MONITOR line such as
+    /// `1339518083.107412 [0 127.0.0.1:60866] "keys" "*"`.
+    pub fn parse_monitor_line(line: &str) -> Option<MonitorEntry> {
+        let (timestamp, rest) = line.split_once(' ')?;
+        let (source, rest) = rest.strip_prefix('[')?.split_once(']')?;
+        let (db, client) = source.split_once(' ')?;
+        let mut words = split_args(rest.trim().as_bytes())
+            .ok()?
+            .into_iter()
+            .map(|word| String::from_utf8_lossy(&word).into_owned());
+
+        Some(MonitorEntry {
+            timestamp: timestamp.parse().ok()?,
+            db: db.parse().ok()?,
+            client: client.into(),
+            command: words.next()?,
+            args: words.collect(),
+            raw: line.into(),
+        })
+    }
+
+    /// Splits a line into arguments the same way redis-cli does, honouring
+    /// double quotes with `\n`, `\xHH` style escapes and single quotes with
+    /// only `\'`. The error names what is wrong with the quoting.
+    pub fn split_args(bytes: &[u8]) -> Result<Vec<Vec<u8>>, &'static str> {
+        const UNBALANCED: &str = "unbalanced quotes";
+        const NO_SPACE: &str = "closing quote must be followed by a space";
+        let mut args = Vec::<Vec<u8>>::new();
+        let mut i = 0;
+
+        loop {
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if i >= bytes.len() {
+                return Ok(args);
+            }
+
+            let mut current = Vec::<u8>::new();
+            let mut in_double_quotes = false;
+            let mut in_single_quotes = false;
+            loop {
+                if in_double_quotes {
+                    let c = *bytes.get(i).ok_or(UNBALANCED)?;
+                    let hex = |at: usize| bytes.get(at).and_then(|c| (*c as char).to_digit(16));
+                    if c == b'\\' && bytes.get(i + 1) == Some(&b'x') {
+                        match (hex(i + 2), hex(i + 3)) {
+                            (Some(high), Some(low)) => {
+                                current.push((high * 16 + low) as u8);
+                                i += 3;
+                            }
+                            _ => {
+                                i += 1;
+                                current.push(b'x');
+                            }
+                        }
+                    } else if c == b'\\' && i + 1 < bytes.len() {
+                        i += 1;
+                        current.push(match bytes[i] {
+                            b'n' => b'\n',
+                            b'r' => b'\r',
+                            b't' => b'\t',
+                            b'b' => 8,
+                            b'a' => 7,
+                            other => other,
+                        });
+                    } else if c == b'"' {
+                        // closing quote must be followed by a space or nothing at all
+                        if i + 1 < bytes.len() && !bytes[i + 1].is_ascii_whitespace() {
+                            return Err(NO_SPACE);
+                        }
+                        i += 1;
+                        break;
+                    } else {
+                        current.push(c);
+                    }
+                } else if in_single_quotes {
+                    let c = *bytes.get(i).ok_or(UNBALANCED)?;
+                    if c == b'\\' && bytes.get(i + 1) == Some(&b'\'') {
+                        i += 1;
+                        current.push(b'\'');
+                    } else if c == b'\'' {
+                        if i + 1 < bytes.len() && !bytes[i + 1].is_ascii_whitespace() {
+                            return Err(NO_SPACE);
+                        }
+                        i += 1;
+                        break;
+                    } else {
+                        current.push(c);
+                    }
+                } else {
+                    match bytes.get(i) {
+                        None => break,
+                        Some(c) if c.is_ascii_whitespace() => break,
+                        Some(b'"') => in_double_quotes = true,
+                        Some(b'\'') => in_single_quotes = true,
+                        Some(c) => current.push(*c),
+                    }
+                }
+                i += 1;
+            }
+            args.push(current);
+        }
+    }
+
+    /// Orders names so that digit runs compare by value, putting `item:2`
+    /// before `item:10`. Ties fall back to plain string order.
+    pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+        let mut a_chars = a.chars().peekable();
+        let mut b_chars = b.chars().peekable();
+
+        loop {
+            match (a_chars.peek().copied(), b_chars.peek().copied()) {
+                (None, None) => return a.cmp(b),
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                    let mut a_digits = String::new();
+                    while let Some(c) = a_chars.next_if(|c| c.is_ascii_digit()) {
+                        a_digits.push(c);
+                    }
+                    let mut b_digits = String::new();
+                    while let Some(c) = b_chars.next_if(|c| c.is_ascii_digit()) {
+                        b_digits.push(c);
+                    }
+
+                    let a_number = a_digits.trim_start_matches('0');
+                    let b_number = b_digits.trim_start_matches('0');
+                    let order = a_number
+                        .len()
+                        .cmp(&b_number.len())
+                        .then_with(|| a_number.cmp(b_number));
+                    if order != Ordering::Equal {
+                        return order;
+                    }
+                }
+                (Some(x), Some(y)) => {
+                    if x != y {
+                        return x.cmp(&y);
+                    }
+                    a_chars.next();
+                    b_chars.next();
+                }
+            }
+        }
+    }
+
+    /// Matches `text` against a redis glob style `pattern` supporting `*`,
+    /// `?`, `[...]` character classes and `\\` escapes.
+    pub fn glob_match(pattern: &str, text: &str) -> bool {
+        /// The index of the `]` closing the class that opens `pattern`.
+        fn class_end(pattern: &[char]) -> Option<usize> {
+            let mut i = 1;
+            while i < pattern.len() {
+                match pattern[i] {
+                    '\\' => i += 2,
+                    ']' => return Some(i),
+                    _ => i += 1,
+                }
+            }
+            None
+        }
+
+        /// Matches `c` against the token that starts `pattern` and returns
+        /// how many pattern characters the token took.
+        fn match_token(pattern: &[char], c: char) -> Option<usize> {
+            match *pattern.first()? {
+                '?' => Some(1),
+                '[' => {
+                    let end = match class_end(pattern) {
+                        Some(end) => end,
+                        None => return if c == '[' { Some(1) } else { None },
+                    };
+                    let literal = |i: usize| match pattern[i] {
+                        '\\' if i + 1 < end => (pattern[i + 1], 2),
+                        c => (c, 1),
+                    };
+                    let negate = pattern.get(1) == Some(&'^');
+                    let mut i = if negate { 2 } else { 1 };
+                    let mut found = false;
+                    while i < end {
+                        let (start, len) = literal(i);
+                        i += len;
+                        let mut stop = start;
+                        if i + 1 < end && pattern[i] == '-' {
+                            let (range_stop, len) = literal(i + 1);
+                            stop = range_stop;
+                            i += len + 1;
+                        }
+                        found |= start.min(stop) <= c && c <= start.max(stop);
+                    }
+                    if found != negate {
+                        Some(end + 1)
+                    } else {
+                        None
+                    }
+                }
+                '\\' if pattern.len() > 1 => {
+                    if pattern[1] == c {
+                        Some(2)
+                    } else {
+                        None
+                    }
+                }
+                p => {
+                    if p == c {
+                        Some(1)
+                    } else {
+                        None
+                    }
+                }
+            }
+        }
+
+        let pattern: Vec<char> = pattern.chars().collect();
+        let text: Vec<char> = text.chars().collect();
+        let mut p = 0;
+        let mut t = 0;
+        // on a mismatch the last `*` swallows one more character and the
+        // rest of the pattern is tried again from there
+        let mut last_star = None;
+        while t < text.len() {
+            if pattern.get(p) == Some(&'*') {
+                p += 1;
+                last_star = Some((p, t));
+                continue;
+            }
+            match match_token(&pattern[p..], text[t]) {
+                Some(len) => {
+                    p += len;
+                    t += 1;
+                }
+                None => match last_star {
+                    Some((star_p, star_t)) => {
+                        p = star_p;
+                        t = star_t + 1;
+                        last_star = Some((star_p, t));
+                    }
+                    None => return false,
+                },
+            }
+        }
+        pattern[p..].iter().all(|&c| c == '*')
+    }
+
+    /// Delimiter used for new connections, matching the common `a:b:c` style.
+    pub const DEFAULT_NAMESPACE_DELIMITERS: &str = ":";
+
+    /// Escapes glob metacharacters so `text` only matches itself.
+    pub fn escape_glob(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    /// Builds the SCAN MATCH patterns for a namespace row: one for a key
+    /// named exactly `path` and one for every key below it, accepting any of
+    /// `delimiters` between its parts. The two never match the same key.
+    pub fn namespace_patterns(path: &[&str], delimiters: &str) -> Vec<String> {
+        let delimiter = if delimiters.chars().count() == 1 {
+            escape_glob(delimiters)
+        } else {
+            let class: String = delimiters
+                .chars()
+                .map(|c| match c {
+                    ']' | '\\' | '^' | '-' => format!("\\{}", c),
+                    _ => c.to_string(),
+                })
+                .collect();
+            format!("[{}]", class)
+        };
+        let key_pattern = path
+            .iter()
+            .map(|part| escape_glob(part))
+            .collect::<Vec<String>>()
+            .join(&delimiter);
+        let below_pattern = format!("{}{}*", key_pattern, delimiter);
+        vec![key_pattern, below_pattern]
+    }
+
+    /// Splits a key on any of the `delimiters` characters. An empty delimiter
+    /// set keeps the key whole, which groups every key into a flat list.
+    pub fn split_key<'a>(key: &'a str, delimiters: &str) -> Vec<&'a str> {
+        if delimiters.is_empty() {
+            vec![key]
+        } else {
+            key.split(|c| delimiters.contains(c)).collect()
+        }
+    }
+
+    pub fn convert_keys_to_namespaces<'a>(
+        keys: impl IntoIterator<Item = &'a String>,
+        delimiters: &str,
+    ) -> HashMap<String, RedisNamespace> {
         let mut namespaces = HashMap::<String, RedisNamespace>::new();
 
         let mut empty_namespace = RedisNamespace {
@@ -75,11 +821,11 @@ pub(crate) mod redislogic {
         };
 
         for key in keys {
-            let parts: Vec<&str> = key.split(":").collect();
+            let parts = split_key(key, delimiters);
             if parts.len() == 1 {
                 empty_namespace.keys.push(key.clone());
             } else {
-                add_key_to_namespaces(parts, &mut namespaces, 0);
+                add_key_to_namespaces(key, &parts, &mut namespaces, 0);
             }
         }
         namespaces.insert("".into(), empty_namespace);
@@ -87,7 +833,8 @@ pub(crate) mod redislogic {
     }
 
     pub fn add_key_to_namespaces(
-        parts: Vec<&str>,
+        key: &str,
+        parts: &[&str],
         current_namespace: &mut HashMap<String, RedisNamespace>,
         part_index: usize,
     ) {
@@ -110,9 +857,14 @@ pub(crate) mod redislogic {
         };
 
         if part_index == parts.len() - 1 {
-            next_namespace.keys.push(parts.join(":"));
+            next_namespace.keys.push(key.into());
         } else {
-            add_key_to_namespaces(parts, &mut next_namespace.sub_namespaces, part_index + 1);
+            add_key_to_namespaces(
+                key,
+                parts,
+                &mut next_namespace.sub_namespaces,
+                part_index + 1,
+            );
         }
     }
 
@@ -122,6 +874,87 @@ pub(crate) mod redislogic {
         pub keys: Vec<String>,
     }
 
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct KeyspaceDb {
+        pub db: i64,
+        pub keys: u64,
+        /// How many of the keys have an expiry.
+        pub expires: u64,
+        pub avg_ttl_millis: u64,
+    }
+
+    impl fmt::Display for KeyspaceDb {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "db{}: {} keys, {} expiring",
+                self.db, self.keys, self.expires
+            )?;
+            if self.avg_ttl_millis > 0 {
+                write!(
+                    f,
+                    ", avg ttl {}",
+                    format_duration(self.avg_ttl_millis / 1000)
+                )?;
+            }
+            Ok(())
+        }
+    }
+
+    pub struct InfoSection {
+        pub name: String,
+        pub entries: Vec<(String, String)>,
+    }
+
+    pub struct SlowlogEntry {
+        pub id: u64,
+        pub timestamp: i64,
+        pub duration_micros: u64,
+        pub command: Vec<String>,
+        pub client_addr: String,
+        pub client_name: String,
+    }
+
+    pub struct ClientInfo {
+        pub id: u64,
+        pub addr: String,
+        pub name: String,
+        pub age: u64,
+        pub idle: u64,
+        pub db: i64,
+        pub cmd: String,
+        pub memory: u64,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct KeyMetadata {
+        pub key_type: String,
+        /// Milliseconds until the key expires, -1 without an expiry and -2
+        /// when the key no longer exists.
+        pub ttl_millis: i64,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct KeyMemory {
+        pub key: String,
+        pub key_type: String,
+        /// What MEMORY USAGE reports, the key name and overhead included.
+        pub bytes: u64,
+        /// Length of lists, sets, sorted sets, hashes and streams.
+        pub elements: Option<u64>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct MonitorEntry {
+        pub timestamp: f64,
+        pub db: i64,
+        pub client: String,
+        pub command: String,
+        pub args: Vec<String>,
+        pub raw: String,
+    }
+
+    #[derive(Debug, Clone)]
     pub enum RedisValue {
         String(String),
         List(Vec<String>),
@@ -130,4 +963,227 @@ pub(crate) mod redislogic {
         Hash(HashMap<String, String>),
         Null,
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn split_args_follows_redis_cli_quoting() {
+            assert_eq!(
+                split_args(br#"  SET  'it\'s' "a\x41\n"  plain"#),
+                Ok(vec![
+                    b"SET".to_vec(),
+                    b"it's".to_vec(),
+                    b"aA\n".to_vec(),
+                    b"plain".to_vec()
+                ])
+            );
+            assert_eq!(split_args(b""), Ok(Vec::new()));
+            assert!(split_args(br#"SET "open"#).is_err());
+            assert!(split_args(br#"SET "a"b"#).is_err());
+        }
+
+        #[test]
+        fn split_args_joins_quotes_inside_a_word() {
+            assert_eq!(
+                split_args(br#"a"b c" d"#),
+                Ok(vec![b"ab c".to_vec(), b"d".to_vec()])
+            );
+            assert!(split_args(br#"a"b c"d"#).is_err());
+            assert_eq!(split_args(br#""\xzz""#), Ok(vec![b"xzz".to_vec()]));
+        }
+
+        #[test]
+        fn monitor_lines_split_into_command_and_args() {
+            let entry =
+                parse_monitor_line(r#"1339518083.107412 [0 127.0.0.1:60866] "set" "a b" "\x00""#)
+                    .unwrap();
+            assert_eq!(entry.db, 0);
+            assert_eq!(entry.client, "127.0.0.1:60866");
+            assert_eq!(entry.command, "set");
+            assert_eq!(entry.args, vec!["a b".to_string(), "\0".to_string()]);
+            assert!(parse_monitor_line(r#"1339518083.1 [0 127.0.0.1:1] "set"#).is_none());
+        }
+
+        #[test]
+        fn natural_cmp_orders_digit_runs_by_value() {
+            assert_eq!(natural_cmp("item:2", "item:10"), Ordering::Less);
+            assert_eq!(natural_cmp("a10b", "a9c"), Ordering::Greater);
+            assert_eq!(natural_cmp("v1.10", "v1.9"), Ordering::Greater);
+            assert_eq!(
+                natural_cmp("99999999999999999999", "100000000000000000000"),
+                Ordering::Less
+            );
+        }
+
+        #[test]
+        fn natural_cmp_breaks_leading_zero_ties_by_text() {
+            assert_eq!(natural_cmp("x007y", "x7a"), Ordering::Greater);
+            assert_eq!(natural_cmp("file01", "file1"), Ordering::Less);
+            assert_eq!(natural_cmp("file1", "file01"), Ordering::Greater);
+            assert_eq!(natural_cmp("file002", "file1"), Ordering::Greater);
+        }
+
+        #[test]
+        fn natural_cmp_is_case_sensitive() {
+            assert_eq!(natural_cmp("Key2", "key10"), Ordering::Less);
+            assert_eq!(natural_cmp("b", "A"), Ordering::Greater);
+        }
+
+        #[test]
+        fn natural_cmp_puts_prefixes_first() {
+            assert_eq!(natural_cmp("user", "user:1"), Ordering::Less);
+            assert_eq!(natural_cmp("user:1:profile", "user:1"), Ordering::Greater);
+            assert_eq!(natural_cmp("user:12", "user:12"), Ordering::Equal);
+            assert_eq!(natural_cmp("", ""), Ordering::Equal);
+        }
+
+        #[test]
+        fn glob_match_star_takes_any_run() {
+            assert!(glob_match("*", ""));
+            assert!(glob_match("a*", "abc"));
+            assert!(glob_match("a*b*c", "aXbYbc"));
+            assert!(glob_match("*:*:x", "a:b:c:x"));
+            assert!(!glob_match("a*b", "abc"));
+            assert!(!glob_match("*a*a*a*a*a*a*b", &"a".repeat(100)));
+        }
+
+        #[test]
+        fn glob_match_question_mark_takes_one_char() {
+            assert!(glob_match("h?llo", "hello"));
+            assert!(glob_match("??", "éa"));
+            assert!(!glob_match("h?llo", "hllo"));
+            assert!(!glob_match("?", ""));
+        }
+
+        #[test]
+        fn glob_match_classes_and_ranges() {
+            assert!(glob_match("[a-c]x", "bx"));
+            assert!(glob_match("[abc]", "c"));
+            assert!(glob_match("[c-a]", "b"));
+            assert!(glob_match("[a-]", "-"));
+            assert!(!glob_match("[a-c]x", "dx"));
+            assert!(glob_match("[^x]y", "ay"));
+            assert!(!glob_match("[^x]y", "xy"));
+            assert!(!glob_match("[^x]", ""));
+            assert!(glob_match("[ab", "[ab"));
+        }
+
+        #[test]
+        fn glob_match_escapes() {
+            assert!(glob_match(r"\*", "*"));
+            assert!(!glob_match(r"\*", "a"));
+            assert!(glob_match(r"a\?", "a?"));
+            assert!(!glob_match(r"a\?", "ab"));
+            assert!(glob_match(r"[\]x]", "]"));
+            assert!(glob_match(r"[\^]", "^"));
+            assert!(glob_match(r"\[a]", "[a]"));
+            assert!(glob_match(r"a\", r"a\"));
+        }
+
+        #[test]
+        fn escape_glob_matches_only_itself() {
+            let name = r"we*rd?[key]\x";
+            let escaped = escape_glob(name);
+            assert_eq!(escaped, r"we\*rd\?\[key\]\\x");
+            assert!(glob_match(&escaped, name));
+            assert!(!glob_match(&escaped, r"weXrdY[key]\x"));
+            assert!(!glob_match(&escaped, "werd?k"));
+            assert_eq!(escape_glob("plain:key"), "plain:key");
+        }
+
+        #[test]
+        fn namespace_patterns_split_key_and_children() {
+            let patterns = namespace_patterns(&["cache", "tenant42"], ":");
+            assert_eq!(patterns, vec!["cache:tenant42", "cache:tenant42:*"]);
+            assert!(glob_match(&patterns[0], "cache:tenant42"));
+            assert!(glob_match(&patterns[1], "cache:tenant42:x"));
+            for pattern in &patterns {
+                assert!(!glob_match(pattern, "cache:tenant420:x"));
+                assert!(!glob_match(pattern, "cache:tenant420"));
+            }
+            assert!(!glob_match(&patterns[1], "cache:tenant42"));
+        }
+
+        #[test]
+        fn namespace_patterns_escape_names_and_delimiters() {
+            let patterns = namespace_patterns(&["a*", "[b]"], "*");
+            assert_eq!(patterns, vec![r"a\*\*\[b\]", r"a\*\*\[b\]\**"]);
+            assert!(glob_match(&patterns[0], "a**[b]"));
+            assert!(!glob_match(&patterns[0], "ab*[b]"));
+            assert!(glob_match(&patterns[1], "a**[b]*c"));
+
+            let patterns = namespace_patterns(&["x?", r"y\"], ":/");
+            assert_eq!(patterns, vec![r"x\?[:/]y\\", r"x\?[:/]y\\[:/]*"]);
+            assert!(glob_match(&patterns[0], r"x?/y\"));
+            assert!(glob_match(&patterns[1], r"x?:y\/z"));
+            assert!(!glob_match(&patterns[0], r"xa/y\"));
+            assert!(!glob_match(&patterns[0], r"x?-y\"));
+
+            let patterns = namespace_patterns(&["a", "b"], "^-]");
+            assert_eq!(patterns[0], r"a[\^\-\]]b");
+            for key in &["a^b", "a-b", "a]b"] {
+                assert!(glob_match(&patterns[0], key));
+            }
+            assert!(!glob_match(&patterns[0], "a_b"));
+        }
+
+        #[test]
+        fn client_list_reads_known_fields() {
+            let list = "id=3 addr=127.0.0.1:51234 laddr=127.0.0.1:6379 fd=8 name= age=12 idle=0 \
+                flags=N db=0 sub=0 psub=0 ssub=0 multi=-1 qbuf=26 qbuf-free=20448 argv-mem=10 \
+                multi-mem=0 rbs=1024 rbp=0 obl=0 oll=0 omem=0 tot-mem=22298 events=r \
+                cmd=client|list user=default redir=-1 resp=2 lib-name= lib-ver=\n\
+                id=7 addr=10.0.0.5:40000 fd=9 name=worker age=300 idle=5 flags=N db=2 sub=0 \
+                psub=0 multi=-1 qbuf=0 qbuf-free=0 obl=0 oll=0 omem=512 events=r cmd=blpop\n";
+            let clients = parse_client_list(list);
+            assert_eq!(clients.len(), 2);
+
+            let current = &clients[0];
+            assert_eq!(current.id, 3);
+            assert_eq!(current.addr, "127.0.0.1:51234");
+            assert_eq!(current.name, "");
+            assert_eq!((current.age, current.idle, current.db), (12, 0, 0));
+            assert_eq!(current.cmd, "client|list");
+            assert_eq!(current.memory, 22298);
+
+            // a pre-6.0 server without tot-mem falls back to omem
+            let worker = &clients[1];
+            assert_eq!((worker.id, worker.db), (7, 2));
+            assert_eq!(worker.name, "worker");
+            assert_eq!(worker.memory, 512);
+        }
+
+        #[test]
+        fn keyspace_lines_read_counts_and_ttl() {
+            assert_eq!(
+                parse_keyspace_line("db0:keys=12,expires=3,avg_ttl=5000\r"),
+                Some(KeyspaceDb {
+                    db: 0,
+                    keys: 12,
+                    expires: 3,
+                    avg_ttl_millis: 5000,
+                })
+            );
+            assert_eq!(
+                parse_keyspace_line("db15:keys=1,expires=0,avg_ttl=0,subexpiry=0,future=x"),
+                Some(KeyspaceDb {
+                    db: 15,
+                    keys: 1,
+                    expires: 0,
+                    avg_ttl_millis: 0,
+                })
+            );
+        }
+
+        #[test]
+        fn malformed_keyspace_lines_are_skipped() {
+            assert_eq!(parse_keyspace_line("# Keyspace"), None);
+            assert_eq!(parse_keyspace_line(""), None);
+            assert_eq!(parse_keyspace_line("dbx:keys=1"), None);
+            assert_eq!(parse_keyspace_line("db0:keys=many,expires=0"), None);
+            assert_eq!(parse_keyspace_line("db0:keys"), None);
+        }
+    }
 }
@@ -0,0 +1,294 @@
+use std::{fmt, fs, io};
+
+use crate::redislogic::redislogic::split_args;
+use crate::snapshot::{KeysSnapshot, SnapshotBytes, SnapshotKey, SnapshotValue};
+
+/// Commands sent per pipeline round trip when running a script.
+const COMMAND_BATCH_SIZE: usize = 1000;
+
+/// A command name followed by its arguments, all as raw bytes.
+pub type RedisCommand = Vec<Vec<u8>>;
+
+/// The file formats keys can be exported to and imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Json,
+    Commands,
+}
+
+impl FileFormat {
+    pub const ALL: [FileFormat; 2] = [FileFormat::Json, FileFormat::Commands];
+
+    pub fn default_path(&self) -> &'static str {
+        match self {
+            FileFormat::Json => "keys.json",
+            FileFormat::Commands => "keys.redis",
+        }
+    }
+}
+
+impl fmt::Display for FileFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FileFormat::Json => "JSON snapshot",
+            FileFormat::Commands => "redis-cli commands",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Quotes an argument the way redis-cli and the inline protocol read it back,
+/// escaping anything that isn't printable ASCII.
+pub fn quote_arg(arg: &[u8]) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for &byte in arg {
+        match byte {
+            b'\\' => quoted.push_str("\\\\"),
+            b'"' => quoted.push_str("\\\""),
+            b'\n' => quoted.push_str("\\n"),
+            b'\r' => quoted.push_str("\\r"),
+            b'\t' => quoted.push_str("\\t"),
+            0x07 => quoted.push_str("\\a"),
+            0x08 => quoted.push_str("\\b"),
+            0x20..=0x7e => quoted.push(byte as char),
+            _ => quoted.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The commands recreating one key. A DEL comes first so that running the
+/// script twice doesn't append to lists and sets.
+fn key_commands(snapshot_key: &SnapshotKey) -> Result<Vec<RedisCommand>, base64::DecodeError> {
+    let key = snapshot_key.key.to_bytes()?;
+    let command = |name: &str| vec![name.as_bytes().to_vec(), key.clone()];
+    let decode = |bytes: &SnapshotBytes| bytes.to_bytes();
+
+    let mut commands = vec![command("DEL")];
+    let write = match &snapshot_key.value {
+        SnapshotValue::String(value) => {
+            let mut set = command("SET");
+            set.push(decode(value)?);
+            set
+        }
+        SnapshotValue::List(items) => {
+            let mut rpush = command("RPUSH");
+            for item in items.iter() {
+                rpush.push(decode(item)?);
+            }
+            rpush
+        }
+        SnapshotValue::Set(members) => {
+            let mut sadd = command("SADD");
+            for member in members.iter() {
+                sadd.push(decode(member)?);
+            }
+            sadd
+        }
+        SnapshotValue::ZSet(members) => {
+            let mut zadd = command("ZADD");
+            for (member, score) in members.iter() {
+                zadd.push(score.clone().into_bytes());
+                zadd.push(decode(member)?);
+            }
+            zadd
+        }
+        SnapshotValue::Hash(fields) => {
+            let mut hset = command("HSET");
+            for (field, value) in fields.iter() {
+                hset.push(decode(field)?);
+                hset.push(decode(value)?);
+            }
+            hset
+        }
+    };
+    if write.len() > 2 {
+        commands.push(write);
+    }
+    if let Some(ttl_millis) = snapshot_key.ttl_millis {
+        let mut pexpire = command("PEXPIRE");
+        pexpire.push(ttl_millis.max(1).to_string().into_bytes());
+        commands.push(pexpire);
+    }
+    Ok(commands)
+}
+
+/// Renders a snapshot as a redis-cli script, one quoted command per line.
+pub fn snapshot_to_script(snapshot: &KeysSnapshot) -> Result<String, base64::DecodeError> {
+    let mut script = String::new();
+    for snapshot_key in snapshot.keys.iter() {
+        for command in key_commands(snapshot_key)? {
+            let line = command
+                .iter()
+                .map(|arg| quote_arg(arg))
+                .collect::<Vec<String>>()
+                .join(" ");
+            script.push_str(&line);
+            script.push('\n');
+        }
+    }
+    Ok(script)
+}
+
+/// Reads either a redis-cli script or RESP mass-insertion data, telling them
+/// apart by the first byte.
+pub fn parse_commands(data: &[u8]) -> Result<Vec<RedisCommand>, String> {
+    match data.iter().find(|byte| !byte.is_ascii_whitespace()) {
+        Some(b'*') => parse_resp(data),
+        _ => parse_script(data),
+    }
+}
+
+fn parse_script(data: &[u8]) -> Result<Vec<RedisCommand>, String> {
+    let mut commands = Vec::<RedisCommand>::new();
+    for (number, line) in data.split(|byte| *byte == b'\n').enumerate() {
+        let args = split_args(line).map_err(|e| format!("line {}: {}", number + 1, e))?;
+        match args.first() {
+            None => {}
+            Some(name) if name.starts_with(b"#") => {}
+            Some(_) => commands.push(args),
+        }
+    }
+    Ok(commands)
+}
+
+fn parse_resp(data: &[u8]) -> Result<Vec<RedisCommand>, String> {
+    let mut commands = Vec::<RedisCommand>::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        if data[pos].is_ascii_whitespace() {
+            pos += 1;
+            continue;
+        }
+        let count = read_resp_header(data, &mut pos, b'*')?;
+        // the count comes from the file, so it doesn't size any allocation
+        let mut command = RedisCommand::new();
+        for _ in 0..count {
+            let len = read_resp_header(data, &mut pos, b'$')?;
+            // `pos` never passes the end of `data`, so this can't overflow
+            if len > data.len() - pos || !data[pos + len..].starts_with(b"\r\n") {
+                return Err(format!("truncated bulk string at byte {}", pos));
+            }
+            let end = pos + len;
+            command.push(data[pos..end].to_vec());
+            pos = end + 2;
+        }
+        commands.push(command);
+    }
+    Ok(commands)
+}
+
+fn read_resp_header(data: &[u8], pos: &mut usize, prefix: u8) -> Result<usize, String> {
+    if data.get(*pos) != Some(&prefix) {
+        return Err(format!("expected '{}' at byte {}", prefix as char, pos));
+    }
+    let line_end = data[*pos..]
+        .windows(2)
+        .position(|window| window == b"\r\n")
+        .map(|offset| *pos + offset)
+        .ok_or_else(|| format!("unterminated header at byte {}", pos))?;
+    let value = std::str::from_utf8(&data[*pos + 1..line_end])
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok())
+        .ok_or_else(|| format!("invalid length at byte {}", pos))?;
+    *pos = line_end + 2;
+    Ok(value)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommandsReport {
+    pub executed: usize,
+    /// The first error of every batch that had one.
+    pub failures: Vec<String>,
+}
+
+/// Sends the commands as pipelines of `COMMAND_BATCH_SIZE`. A failing command
+/// doesn't stop the others, but only the first error of a batch is known.
+pub fn run_commands(con: &mut redis::Connection, commands: &[RedisCommand]) -> CommandsReport {
+    let mut report = CommandsReport::default();
+    for (batch_index, batch) in commands.chunks(COMMAND_BATCH_SIZE).enumerate() {
+        let mut pipe = redis::pipe();
+        for command in batch.iter().filter(|command| !command.is_empty()) {
+            let mut cmd = redis::cmd(&String::from_utf8_lossy(&command[0]));
+            for arg in command[1..].iter() {
+                cmd.arg(&arg[..]);
+            }
+            pipe.add_command(cmd);
+        }
+
+        report.executed += batch.len();
+        if let Err(e) = pipe.query::<Vec<redis::Value>>(con) {
+            let first = batch_index * COMMAND_BATCH_SIZE + 1;
+            report.failures.push(format!(
+                "commands {}-{}: {}",
+                first,
+                first + batch.len() - 1,
+                e
+            ));
+        }
+    }
+    report
+}
+
+pub fn write_script(snapshot: &KeysSnapshot, path: &str) -> io::Result<()> {
+    let script =
+        snapshot_to_script(snapshot).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(path, script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_resp(commands: &[RedisCommand]) -> Vec<u8> {
+        let mut data = Vec::<u8>::new();
+        for command in commands.iter() {
+            data.extend(format!("*{}\r\n", command.len()).into_bytes());
+            for arg in command.iter() {
+                data.extend(format!("${}\r\n", arg.len()).into_bytes());
+                data.extend(arg);
+                data.extend(b"\r\n");
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn quoted_args_split_back_to_the_same_bytes() {
+        let args: RedisCommand = vec![
+            b"SET".to_vec(),
+            b"key with spaces".to_vec(),
+            b"quote\" backslash\\ 'single'".to_vec(),
+            b"\n\r\t\x07\x08".to_vec(),
+            (0..=255).collect(),
+            Vec::new(),
+        ];
+        let line = args
+            .iter()
+            .map(|arg| quote_arg(arg))
+            .collect::<Vec<String>>()
+            .join(" ");
+        assert_eq!(split_args(line.as_bytes()), Ok(args));
+    }
+
+    #[test]
+    fn parse_resp_reads_back_written_commands() {
+        let commands: Vec<RedisCommand> = vec![
+            vec![b"DEL".to_vec(), b"key".to_vec()],
+            vec![b"SET".to_vec(), b"key".to_vec(), b"a\r\nb".to_vec()],
+            vec![b"PING".to_vec()],
+        ];
+        assert_eq!(parse_resp(&to_resp(&commands)), Ok(commands));
+    }
+
+    #[test]
+    fn parse_resp_rejects_bad_lengths() {
+        assert!(parse_resp(b"*1\r\n$10\r\nshort\r\n").is_err());
+        assert!(parse_resp(b"*1\r\n$3\r\nabcd\r\n").is_err());
+        assert!(parse_resp(b"*1\r\n$18446744073709551615\r\nabc\r\n").is_err());
+        assert!(parse_resp(b"*18446744073709551615\r\n").is_err());
+        assert!(parse_resp(b"*1\r\n$x\r\n").is_err());
+    }
+}
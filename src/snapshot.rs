@@ -0,0 +1,430 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::File,
+    io::{self, BufReader, BufWriter},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::redislogic::redislogic::get_key_metadata;
+
+/// Bumped whenever the document layout changes incompatibly.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Text stays readable in the document, anything that isn't UTF-8 is written
+/// as `{"base64": "..."}` instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SnapshotBytes {
+    Text(String),
+    Binary { base64: String },
+}
+
+impl From<Vec<u8>> for SnapshotBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(text) => SnapshotBytes::Text(text),
+            Err(e) => SnapshotBytes::Binary {
+                base64: base64::encode(e.into_bytes()),
+            },
+        }
+    }
+}
+
+impl SnapshotBytes {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        match self {
+            SnapshotBytes::Text(text) => Ok(text.clone().into_bytes()),
+            SnapshotBytes::Binary { base64 } => base64::decode(base64),
+        }
+    }
+}
+
+/// Shows binary bytes lossily, or as the base64 text if that doesn't decode.
+impl fmt::Display for SnapshotBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotBytes::Text(text) => write!(f, "{}", text),
+            SnapshotBytes::Binary { base64 } => match base64::decode(base64) {
+                Ok(bytes) => write!(f, "{}", String::from_utf8_lossy(&bytes)),
+                Err(_) => write!(f, "{}", base64),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum SnapshotValue {
+    String(SnapshotBytes),
+    List(Vec<SnapshotBytes>),
+    Set(Vec<SnapshotBytes>),
+    /// Members with their scores as Redis prints them, so `inf` survives.
+    ZSet(Vec<(SnapshotBytes, String)>),
+    Hash(Vec<(SnapshotBytes, SnapshotBytes)>),
+}
+
+impl SnapshotValue {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            SnapshotValue::String(_) => "string",
+            SnapshotValue::List(_) => "list",
+            SnapshotValue::Set(_) => "set",
+            SnapshotValue::ZSet(_) => "zset",
+            SnapshotValue::Hash(_) => "hash",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotKey {
+    pub key: SnapshotBytes,
+    /// Remaining time to live, absent for keys without an expiry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_millis: Option<i64>,
+    #[serde(flatten)]
+    pub value: SnapshotValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeysSnapshot {
+    pub version: u32,
+    pub keys: Vec<SnapshotKey>,
+}
+
+impl KeysSnapshot {
+    pub fn read(path: &str) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+}
+
+fn into_snapshot_bytes(values: Vec<Vec<u8>>) -> Vec<SnapshotBytes> {
+    values.into_iter().map(SnapshotBytes::from).collect()
+}
+
+/// Reads `keys` with their types and TTLs. Keys that are gone or hold a type
+/// the viewer doesn't support are returned separately.
+pub fn dump_keys(
+    con: &mut redis::Connection,
+    keys: &[String],
+) -> redis::RedisResult<(KeysSnapshot, Vec<String>)> {
+    let metadata = get_key_metadata(con, keys)?;
+    let mut snapshot = KeysSnapshot {
+        version: SNAPSHOT_VERSION,
+        keys: Vec::<SnapshotKey>::new(),
+    };
+    let mut skipped = Vec::<String>::new();
+
+    for (key, metadata) in keys.iter().zip(metadata) {
+        let value = match metadata.key_type.as_str() {
+            "string" => {
+                SnapshotValue::String(redis::cmd("GET").arg(key).query::<Vec<u8>>(con)?.into())
+            }
+            "list" => SnapshotValue::List(into_snapshot_bytes(
+                redis::cmd("LRANGE").arg(key).arg(0).arg(-1).query(con)?,
+            )),
+            "set" => SnapshotValue::Set(into_snapshot_bytes(
+                redis::cmd("SMEMBERS").arg(key).query(con)?,
+            )),
+            "zset" => {
+                let members: Vec<(Vec<u8>, String)> = redis::cmd("ZRANGE")
+                    .arg(key)
+                    .arg(0)
+                    .arg(-1)
+                    .arg("WITHSCORES")
+                    .query(con)?;
+                SnapshotValue::ZSet(
+                    members
+                        .into_iter()
+                        .map(|(member, score)| (member.into(), score))
+                        .collect(),
+                )
+            }
+            "hash" => {
+                let fields: Vec<(Vec<u8>, Vec<u8>)> = redis::cmd("HGETALL").arg(key).query(con)?;
+                SnapshotValue::Hash(
+                    fields
+                        .into_iter()
+                        .map(|(field, value)| (field.into(), value.into()))
+                        .collect(),
+                )
+            }
+            _ => {
+                skipped.push(key.clone());
+                continue;
+            }
+        };
+        snapshot.keys.push(SnapshotKey {
+            key: key.clone().into_bytes().into(),
+            ttl_millis: if metadata.ttl_millis >= 0 {
+                Some(metadata.ttl_millis)
+            } else {
+                None
+            },
+            value,
+        });
+    }
+    Ok((snapshot, skipped))
+}
+
+/// What to do with a snapshot key whose name is already taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+impl ConflictPolicy {
+    pub const ALL: [ConflictPolicy; 3] = [
+        ConflictPolicy::Skip,
+        ConflictPolicy::Overwrite,
+        ConflictPolicy::Rename,
+    ];
+}
+
+impl fmt::Display for ConflictPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ConflictPolicy::Skip => "Skip existing",
+            ConflictPolicy::Overwrite => "Overwrite existing",
+            ConflictPolicy::Rename => "Rename with suffix",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What an import of a snapshot would touch, gathered before writing.
+#[derive(Debug, Clone, Default)]
+pub struct ImportPreview {
+    pub total: usize,
+    pub existing: usize,
+    pub with_ttl: usize,
+    pub types: BTreeMap<&'static str, usize>,
+}
+
+pub fn preview_import(
+    con: &mut redis::Connection,
+    snapshot: &KeysSnapshot,
+) -> redis::RedisResult<ImportPreview> {
+    let mut pipe = redis::pipe();
+    for snapshot_key in snapshot.keys.iter() {
+        let key = snapshot_key.key.to_bytes().map_err(|e| {
+            redis::RedisError::from((
+                redis::ErrorKind::TypeError,
+                "invalid key name",
+                e.to_string(),
+            ))
+        })?;
+        pipe.cmd("EXISTS").arg(key);
+    }
+    let exists: Vec<bool> = pipe.query(con)?;
+
+    let mut preview = ImportPreview {
+        total: snapshot.keys.len(),
+        existing: exists.into_iter().filter(|exists| *exists).count(),
+        ..ImportPreview::default()
+    };
+    for snapshot_key in snapshot.keys.iter() {
+        *preview
+            .types
+            .entry(snapshot_key.value.type_name())
+            .or_insert(0) += 1;
+        if snapshot_key.ttl_millis.is_some() {
+            preview.with_ttl += 1;
+        }
+    }
+    Ok(preview)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub written: usize,
+    pub skipped: usize,
+    pub renamed: usize,
+    /// Keys that could not be written, with the reason.
+    pub failures: Vec<(String, String)>,
+}
+
+/// Writes every snapshot key, resolving name clashes with `policy`. Each key
+/// is written in its own MULTI so a failure leaves no half-written value.
+pub fn import_keys(
+    con: &mut redis::Connection,
+    snapshot: &KeysSnapshot,
+    policy: ConflictPolicy,
+    suffix: &str,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    for snapshot_key in snapshot.keys.iter() {
+        match import_key(con, snapshot_key, policy, suffix) {
+            Ok(ImportOutcome::Written) => report.written += 1,
+            Ok(ImportOutcome::Renamed) => {
+                report.written += 1;
+                report.renamed += 1;
+            }
+            Ok(ImportOutcome::Skipped) => report.skipped += 1,
+            Err(e) => report.failures.push((snapshot_key.key.to_string(), e)),
+        }
+    }
+    report
+}
+
+enum ImportOutcome {
+    Written,
+    Renamed,
+    Skipped,
+}
+
+fn import_key(
+    con: &mut redis::Connection,
+    snapshot_key: &SnapshotKey,
+    policy: ConflictPolicy,
+    suffix: &str,
+) -> Result<ImportOutcome, String> {
+    let exists = |con: &mut redis::Connection, key: &[u8]| -> Result<bool, String> {
+        redis::cmd("EXISTS")
+            .arg(key)
+            .query(con)
+            .map_err(|e| e.to_string())
+    };
+
+    let decode = |bytes: &SnapshotBytes| bytes.to_bytes().map_err(|e| e.to_string());
+    let key = decode(&snapshot_key.key)?;
+    let mut target = key.clone();
+    let mut outcome = ImportOutcome::Written;
+    if exists(con, &target)? {
+        match policy {
+            ConflictPolicy::Skip => return Ok(ImportOutcome::Skipped),
+            ConflictPolicy::Overwrite => {}
+            ConflictPolicy::Rename => {
+                target = [&key[..], suffix.as_bytes()].concat();
+                let mut attempt = 2;
+                while exists(con, &target)? {
+                    target = [&key[..], suffix.as_bytes(), attempt.to_string().as_bytes()].concat();
+                    attempt += 1;
+                }
+                outcome = ImportOutcome::Renamed;
+            }
+        }
+    }
+
+    let mut pipe = redis::pipe();
+    pipe.atomic().cmd("DEL").arg(&target[..]).ignore();
+    match &snapshot_key.value {
+        SnapshotValue::String(value) => {
+            pipe.cmd("SET")
+                .arg(&target[..])
+                .arg(decode(value)?)
+                .ignore();
+        }
+        SnapshotValue::List(items) => {
+            let items = items.iter().map(decode).collect::<Result<Vec<_>, _>>()?;
+            if !items.is_empty() {
+                pipe.cmd("RPUSH").arg(&target[..]).arg(items).ignore();
+            }
+        }
+        SnapshotValue::Set(members) => {
+            let members = members.iter().map(decode).collect::<Result<Vec<_>, _>>()?;
+            if !members.is_empty() {
+                pipe.cmd("SADD").arg(&target[..]).arg(members).ignore();
+            }
+        }
+        SnapshotValue::ZSet(members) => {
+            let mut command = redis::cmd("ZADD");
+            command.arg(&target[..]);
+            for (member, score) in members.iter() {
+                command.arg(score).arg(decode(member)?);
+            }
+            if !members.is_empty() {
+                pipe.add_command(command).ignore();
+            }
+        }
+        SnapshotValue::Hash(fields) => {
+            let mut command = redis::cmd("HSET");
+            command.arg(&target[..]);
+            for (field, value) in fields.iter() {
+                command.arg(decode(field)?).arg(decode(value)?);
+            }
+            if !fields.is_empty() {
+                pipe.add_command(command).ignore();
+            }
+        }
+    }
+    if let Some(ttl_millis) = snapshot_key.ttl_millis {
+        pipe.cmd("PEXPIRE")
+            .arg(&target[..])
+            .arg(ttl_millis.max(1))
+            .ignore();
+    }
+
+    let _: () = pipe.query(con).map_err(|e| e.to_string())?;
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_round_trip_text_and_binary() {
+        let binary = vec![0xff, 0x00, b'a', 0xc3];
+        let snapshot = KeysSnapshot {
+            version: SNAPSHOT_VERSION,
+            keys: vec![
+                SnapshotKey {
+                    key: SnapshotBytes::from(b"user:1".to_vec()),
+                    ttl_millis: Some(5000),
+                    value: SnapshotValue::String(SnapshotBytes::from("héllo".as_bytes().to_vec())),
+                },
+                SnapshotKey {
+                    key: SnapshotBytes::from(binary.clone()),
+                    ttl_millis: None,
+                    value: SnapshotValue::Hash(vec![
+                        (
+                            SnapshotBytes::from(b"field".to_vec()),
+                            SnapshotBytes::from(binary.clone()),
+                        ),
+                        (
+                            SnapshotBytes::from(binary.clone()),
+                            SnapshotBytes::from(b"text".to_vec()),
+                        ),
+                    ]),
+                },
+                SnapshotKey {
+                    key: SnapshotBytes::from(b"scores".to_vec()),
+                    ttl_millis: None,
+                    value: SnapshotValue::ZSet(vec![
+                        (SnapshotBytes::from(b"a".to_vec()), "1.5".to_string()),
+                        (SnapshotBytes::from(binary.clone()), "inf".to_string()),
+                    ]),
+                },
+                SnapshotKey {
+                    key: SnapshotBytes::from(b"queue".to_vec()),
+                    ttl_millis: None,
+                    value: SnapshotValue::List(vec![
+                        SnapshotBytes::from(Vec::new()),
+                        SnapshotBytes::from(binary.clone()),
+                    ]),
+                },
+            ],
+        };
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let read: KeysSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, snapshot);
+        assert_eq!(read.keys[1].key.to_bytes().unwrap(), binary);
+        assert_eq!(
+            read.keys[0].value,
+            SnapshotValue::String(SnapshotBytes::Text("héllo".to_string()))
+        );
+        assert!(matches!(read.keys[1].key, SnapshotBytes::Binary { .. }));
+    }
+}
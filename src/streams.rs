@@ -0,0 +1,537 @@
+use std::{
+    hash::{Hash, Hasher},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use iced::futures::{
+    channel::mpsc::{self, UnboundedSender},
+    stream::BoxStream,
+    StreamExt,
+};
+use iced_native::subscription::Recipe;
+
+use crate::redislogic::redislogic::{
+    connect_redis, get_key_memory, get_key_metadata, parse_monitor_line, unlink_keys,
+    ConnectionSettings, KeyMemory, MonitorEntry,
+};
+
+/// How long a blocking read waits before checking whether the subscription
+/// was dropped, so that dedicated connections close soon after a stop.
+const POLL_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How many matched keys a pattern delete keeps to show as examples.
+const SAMPLE_KEYS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub enum MonitorEvent {
+    Started,
+    Entry(MonitorEntry),
+    Failed(String),
+}
+
+/// Streams MONITOR output from its own connection, so the tab's main
+/// connection stays free for regular commands.
+pub struct MonitorStream {
+    settings: ConnectionSettings,
+}
+
+impl MonitorStream {
+    pub fn new(settings: ConnectionSettings) -> Self {
+        MonitorStream { settings }
+    }
+}
+
+impl<H, I> Recipe<H, I> for MonitorStream
+where
+    H: Hasher,
+{
+    type Output = MonitorEvent;
+
+    fn hash(&self, state: &mut H) {
+        std::any::TypeId::of::<Self>().hash(state);
+        self.settings.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<'static, I>) -> BoxStream<'static, Self::Output> {
+        let (sender, receiver) = mpsc::unbounded();
+        thread::spawn(move || {
+            if let Err(e) = run_monitor(&self.settings, &sender) {
+                let _ = sender.unbounded_send(MonitorEvent::Failed(e.to_string()));
+            }
+        });
+        receiver.boxed()
+    }
+}
+
+fn run_monitor(
+    settings: &ConnectionSettings,
+    sender: &UnboundedSender<MonitorEvent>,
+) -> redis::RedisResult<()> {
+    let mut con = connect_redis(settings)?;
+    con.send_packed_command(&redis::cmd("MONITOR").get_packed_command())?;
+    let _: () = redis::from_redis_value(&con.recv_response()?)?;
+    con.set_read_timeout(Some(POLL_TIMEOUT))?;
+    let _ = sender.unbounded_send(MonitorEvent::Started);
+
+    while !sender.is_closed() {
+        match con.recv_response() {
+            Ok(redis::Value::Status(line)) => {
+                if let Some(entry) = parse_monitor_line(&line) {
+                    let _ = sender.unbounded_send(MonitorEvent::Entry(entry));
+                }
+            }
+            Ok(_) => {}
+            Err(e) if e.is_timeout() => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct PubSubMessage {
+    pub received_at: f64,
+    pub channel: String,
+    pub pattern: Option<String>,
+    pub payload: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum PubSubEvent {
+    Subscribed,
+    Message(PubSubMessage),
+    Failed(String),
+}
+
+/// Listens on its own connection to the given channels and patterns. The
+/// subscription list is part of the hash, so changing it reconnects.
+pub struct PubSubStream {
+    settings: ConnectionSettings,
+    channels: Vec<String>,
+    patterns: Vec<String>,
+    /// Bumped to start a new subscription after the previous one failed.
+    generation: u32,
+}
+
+impl PubSubStream {
+    pub fn new(
+        settings: ConnectionSettings,
+        channels: Vec<String>,
+        patterns: Vec<String>,
+        generation: u32,
+    ) -> Self {
+        PubSubStream {
+            settings,
+            channels,
+            patterns,
+            generation,
+        }
+    }
+}
+
+impl<H, I> Recipe<H, I> for PubSubStream
+where
+    H: Hasher,
+{
+    type Output = PubSubEvent;
+
+    fn hash(&self, state: &mut H) {
+        std::any::TypeId::of::<Self>().hash(state);
+        self.settings.hash(state);
+        self.channels.hash(state);
+        self.patterns.hash(state);
+        self.generation.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<'static, I>) -> BoxStream<'static, Self::Output> {
+        let (sender, receiver) = mpsc::unbounded();
+        thread::spawn(move || {
+            if let Err(e) = run_pubsub(&self, &sender) {
+                let _ = sender.unbounded_send(PubSubEvent::Failed(e.to_string()));
+            }
+        });
+        receiver.boxed()
+    }
+}
+
+fn run_pubsub(
+    stream: &PubSubStream,
+    sender: &UnboundedSender<PubSubEvent>,
+) -> redis::RedisResult<()> {
+    let mut con = connect_redis(&stream.settings)?;
+    let mut pubsub = con.as_pubsub();
+    if !stream.channels.is_empty() {
+        pubsub.subscribe(&stream.channels[..])?;
+    }
+    if !stream.patterns.is_empty() {
+        pubsub.psubscribe(&stream.patterns[..])?;
+    }
+    pubsub.set_read_timeout(Some(POLL_TIMEOUT))?;
+    let _ = sender.unbounded_send(PubSubEvent::Subscribed);
+
+    while !sender.is_closed() {
+        match pubsub.get_message() {
+            Ok(msg) => {
+                let message = PubSubMessage {
+                    received_at: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|elapsed| elapsed.as_secs_f64())
+                        .unwrap_or(0.0),
+                    channel: msg.get_channel_name().into(),
+                    pattern: msg.get_pattern().ok(),
+                    payload: String::from_utf8_lossy(msg.get_payload_bytes()).into_owned(),
+                };
+                let _ = sender.unbounded_send(PubSubEvent::Message(message));
+            }
+            Err(e) if e.is_timeout() => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub enum KeyspaceEvent {
+    Subscribed,
+    Changed { event: String, key: String },
+    Failed(String),
+}
+
+/// Follows `__keyevent@<db>__` notifications for the tab's database. The
+/// server only publishes them when `notify-keyspace-events` enables them.
+pub struct KeyspaceStream {
+    settings: ConnectionSettings,
+}
+
+impl KeyspaceStream {
+    pub fn new(settings: ConnectionSettings) -> Self {
+        KeyspaceStream { settings }
+    }
+}
+
+impl<H, I> Recipe<H, I> for KeyspaceStream
+where
+    H: Hasher,
+{
+    type Output = KeyspaceEvent;
+
+    fn hash(&self, state: &mut H) {
+        std::any::TypeId::of::<Self>().hash(state);
+        self.settings.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<'static, I>) -> BoxStream<'static, Self::Output> {
+        let (sender, receiver) = mpsc::unbounded();
+        thread::spawn(move || {
+            if let Err(e) = run_keyspace(&self.settings, &sender) {
+                let _ = sender.unbounded_send(KeyspaceEvent::Failed(e.to_string()));
+            }
+        });
+        receiver.boxed()
+    }
+}
+
+fn run_keyspace(
+    settings: &ConnectionSettings,
+    sender: &UnboundedSender<KeyspaceEvent>,
+) -> redis::RedisResult<()> {
+    let prefix = format!("__keyevent@{}__:", settings.db);
+    let mut con = connect_redis(settings)?;
+    let mut pubsub = con.as_pubsub();
+    pubsub.psubscribe(format!("{}*", prefix))?;
+    pubsub.set_read_timeout(Some(POLL_TIMEOUT))?;
+    let _ = sender.unbounded_send(KeyspaceEvent::Subscribed);
+
+    while !sender.is_closed() {
+        match pubsub.get_message() {
+            Ok(msg) => {
+                let channel = msg.get_channel_name();
+                let event = channel.strip_prefix(&prefix).unwrap_or(channel).to_string();
+                let key = String::from_utf8_lossy(msg.get_payload_bytes()).into_owned();
+                let _ = sender.unbounded_send(KeyspaceEvent::Changed { event, key });
+            }
+            Err(e) if e.is_timeout() => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PatternDeleteProgress {
+    pub matched: u64,
+    pub deleted: u64,
+    pub samples: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum PatternDeleteEvent {
+    Progress(PatternDeleteProgress),
+    Finished(PatternDeleteProgress),
+    Failed(String),
+}
+
+/// Walks the keyspace with SCAN MATCH for each pattern in turn on its own
+/// connection and UNLINKs each
+/// batch of matches. A dry run only counts and samples them. Dropping the
+/// subscription stops it before the next batch.
+pub struct PatternDeleteStream {
+    settings: ConnectionSettings,
+    patterns: Vec<String>,
+    dry_run: bool,
+}
+
+impl PatternDeleteStream {
+    pub fn new(settings: ConnectionSettings, patterns: Vec<String>, dry_run: bool) -> Self {
+        PatternDeleteStream {
+            settings,
+            patterns,
+            dry_run,
+        }
+    }
+}
+
+impl<H, I> Recipe<H, I> for PatternDeleteStream
+where
+    H: Hasher,
+{
+    type Output = PatternDeleteEvent;
+
+    fn hash(&self, state: &mut H) {
+        std::any::TypeId::of::<Self>().hash(state);
+        self.settings.hash(state);
+        self.patterns.hash(state);
+        self.dry_run.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<'static, I>) -> BoxStream<'static, Self::Output> {
+        let (sender, receiver) = mpsc::unbounded();
+        thread::spawn(move || {
+            if let Err(e) = run_pattern_delete(&self, &sender) {
+                let _ = sender.unbounded_send(PatternDeleteEvent::Failed(e.to_string()));
+            }
+        });
+        receiver.boxed()
+    }
+}
+
+fn run_pattern_delete(
+    stream: &PatternDeleteStream,
+    sender: &UnboundedSender<PatternDeleteEvent>,
+) -> redis::RedisResult<()> {
+    let mut con = connect_redis(&stream.settings)?;
+    let mut progress = PatternDeleteProgress::default();
+    for pattern in stream.patterns.iter() {
+        let mut cursor: u64 = 0;
+        loop {
+            if sender.is_closed() {
+                return Ok(());
+            }
+            let (next_cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(1000)
+                .query(&mut con)?;
+
+            progress.matched += keys.len() as u64;
+            let missing = SAMPLE_KEYS.saturating_sub(progress.samples.len());
+            progress.samples.extend(keys.iter().take(missing).cloned());
+            if !stream.dry_run && !keys.is_empty() {
+                progress.deleted += unlink_keys(&mut con, &keys)?;
+            }
+
+            cursor = next_cursor;
+            if cursor == 0 {
+                break;
+            }
+            let _ = sender.unbounded_send(PatternDeleteEvent::Progress(progress.clone()));
+        }
+    }
+    let _ = sender.unbounded_send(PatternDeleteEvent::Finished(progress));
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub enum KeyScanEvent {
+    /// The keys of one SCAN batch.
+    Progress(Vec<String>),
+    Finished,
+    Failed(String),
+}
+
+/// Lists the keyspace with SCAN on its own connection, optionally only the
+/// keys of one type. Unlike KEYS this neither blocks the server nor the UI on
+/// a big keyspace. `generation` restarts the scan when the keys are reloaded
+/// while one is still running.
+pub struct KeyScanStream {
+    settings: ConnectionSettings,
+    type_name: Option<String>,
+    generation: u64,
+}
+
+impl KeyScanStream {
+    pub fn new(settings: ConnectionSettings, type_name: Option<String>, generation: u64) -> Self {
+        KeyScanStream {
+            settings,
+            type_name,
+            generation,
+        }
+    }
+}
+
+impl<H, I> Recipe<H, I> for KeyScanStream
+where
+    H: Hasher,
+{
+    type Output = KeyScanEvent;
+
+    fn hash(&self, state: &mut H) {
+        std::any::TypeId::of::<Self>().hash(state);
+        self.settings.hash(state);
+        self.type_name.hash(state);
+        self.generation.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<'static, I>) -> BoxStream<'static, Self::Output> {
+        let (sender, receiver) = mpsc::unbounded();
+        thread::spawn(move || {
+            if let Err(e) = run_key_scan(&self, &sender) {
+                let _ = sender.unbounded_send(KeyScanEvent::Failed(e.to_string()));
+            }
+        });
+        receiver.boxed()
+    }
+}
+
+/// Filters by type with the TYPE option of SCAN, or with pipelined TYPE calls
+/// on servers before 6.0 that reject it.
+fn run_key_scan(
+    stream: &KeyScanStream,
+    sender: &UnboundedSender<KeyScanEvent>,
+) -> redis::RedisResult<()> {
+    let mut con = connect_redis(&stream.settings)?;
+    let mut has_type_option = true;
+    let mut cursor: u64 = 0;
+    loop {
+        if sender.is_closed() {
+            return Ok(());
+        }
+        let mut command = redis::cmd("SCAN");
+        command.arg(cursor).arg("COUNT").arg(1000);
+        if let (Some(type_name), true) = (&stream.type_name, has_type_option) {
+            command.arg("TYPE").arg(type_name);
+        }
+        let (next_cursor, mut keys): (u64, Vec<String>) = match command.query(&mut con) {
+            Ok(result) => result,
+            Err(e)
+                if e.kind() == redis::ErrorKind::ResponseError
+                    && stream.type_name.is_some()
+                    && has_type_option
+                    && cursor == 0 =>
+            {
+                has_type_option = false;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let (Some(type_name), false) = (&stream.type_name, has_type_option) {
+            if !keys.is_empty() {
+                let metadata = get_key_metadata(&mut con, &keys)?;
+                let mut metadata = metadata.into_iter();
+                keys.retain(|_| {
+                    metadata
+                        .next()
+                        .is_some_and(|metadata| metadata.key_type == *type_name)
+                });
+            }
+        }
+        if !keys.is_empty() {
+            let _ = sender.unbounded_send(KeyScanEvent::Progress(keys));
+        }
+
+        cursor = next_cursor;
+        if cursor == 0 {
+            break;
+        }
+    }
+    let _ = sender.unbounded_send(KeyScanEvent::Finished);
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub enum MemoryScanEvent {
+    /// The measurements of one SCAN batch.
+    Progress(Vec<KeyMemory>),
+    Finished,
+    Failed(String),
+}
+
+/// Walks the whole keyspace with SCAN and measures every key on a dedicated
+/// connection, so a big keyspace doesn't block the UI.
+pub struct MemoryScanStream {
+    settings: ConnectionSettings,
+    samples: usize,
+}
+
+impl MemoryScanStream {
+    pub fn new(settings: ConnectionSettings, samples: usize) -> Self {
+        MemoryScanStream { settings, samples }
+    }
+}
+
+impl<H, I> Recipe<H, I> for MemoryScanStream
+where
+    H: Hasher,
+{
+    type Output = MemoryScanEvent;
+
+    fn hash(&self, state: &mut H) {
+        std::any::TypeId::of::<Self>().hash(state);
+        self.settings.hash(state);
+        self.samples.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<'static, I>) -> BoxStream<'static, Self::Output> {
+        let (sender, receiver) = mpsc::unbounded();
+        thread::spawn(move || {
+            if let Err(e) = run_memory_scan(&self, &sender) {
+                let _ = sender.unbounded_send(MemoryScanEvent::Failed(e.to_string()));
+            }
+        });
+        receiver.boxed()
+    }
+}
+
+fn run_memory_scan(
+    stream: &MemoryScanStream,
+    sender: &UnboundedSender<MemoryScanEvent>,
+) -> redis::RedisResult<()> {
+    let mut con = connect_redis(&stream.settings)?;
+    let mut cursor: u64 = 0;
+    loop {
+        if sender.is_closed() {
+            return Ok(());
+        }
+        let (next_cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("COUNT")
+            .arg(1000)
+            .query(&mut con)?;
+
+        if !keys.is_empty() {
+            let measured = get_key_memory(&mut con, &keys, stream.samples)?;
+            let _ = sender.unbounded_send(MemoryScanEvent::Progress(measured));
+        }
+
+        cursor = next_cursor;
+        if cursor == 0 {
+            break;
+        }
+    }
+    let _ = sender.unbounded_send(MemoryScanEvent::Finished);
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use iced::{container, radio, rule, scrollable};
+use iced::{button, container, radio, rule, scrollable};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Theme {
@@ -52,8 +52,24 @@ impl From<Theme> for Box<dyn rule::StyleSheet> {
     }
 }
 
+/// The button of a server tab, set apart while its tab is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TabButton {
+    pub is_active: bool,
+}
+
+impl From<TabButton> for Box<dyn button::StyleSheet> {
+    fn from(tab_button: TabButton) -> Self {
+        if tab_button.is_active {
+            dark::ActiveTab.into()
+        } else {
+            Default::default()
+        }
+    }
+}
+
 mod dark {
-    use iced::{container, radio, rule, scrollable, Color};
+    use iced::{button, container, radio, rule, scrollable, Color};
 
     const BACKGROUND: Color = Color::from_rgb(
         0x36 as f32 / 255.0,
@@ -127,6 +143,21 @@ mod dark {
         }
     }
 
+    pub struct ActiveTab;
+
+    impl button::StyleSheet for ActiveTab {
+        fn active(&self) -> button::Style {
+            button::Style {
+                background: ACTIVE.into(),
+                border_radius: 2.0,
+                border_width: 1.0,
+                border_color: ACCENT,
+                text_color: Color::WHITE,
+                ..button::Style::default()
+            }
+        }
+    }
+
     pub struct Scrollable;
 
     impl scrollable::StyleSheet for Scrollable {
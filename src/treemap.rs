@@ -0,0 +1,252 @@
+use iced::{
+    canvas::{self, event, Cursor, Event, Frame, Geometry, Text},
+    mouse, Color, Point, Rectangle, Size,
+};
+
+use crate::format::format_bytes;
+use crate::memory::MemoryTreeNode;
+
+/// Tiles beyond the biggest ones are merged into a single one.
+const MAX_TILES: usize = 100;
+const TILE_GAP: f32 = 1.0;
+const LABEL_SIZE: f32 = 14.0;
+/// Rough width of a label character, to cut labels that would overflow.
+const LABEL_CHAR_WIDTH: f32 = 7.0;
+
+const BACKGROUND: Color = Color::from_rgb(
+    0x2E as f32 / 255.0,
+    0x33 as f32 / 255.0,
+    0x38 as f32 / 255.0,
+);
+
+fn type_color(key_type: &str) -> Color {
+    let (r, g, b) = match key_type {
+        "string" => (0x4C, 0x78, 0xA8),
+        "list" => (0xF5, 0x85, 0x18),
+        "set" => (0x54, 0xA2, 0x4B),
+        "zset" => (0xB2, 0x79, 0xA2),
+        "hash" => (0xE4, 0x57, 0x56),
+        "stream" => (0x72, 0xB7, 0xB2),
+        _ => (0x9D, 0x75, 0x5D),
+    };
+    Color::from_rgb8(r, g, b)
+}
+
+#[derive(Debug, Clone)]
+pub enum TileTarget {
+    Namespace(String),
+    Key(String),
+    /// The merged tiles too small to show on their own.
+    Rest,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tile {
+    pub label: String,
+    pub target: TileTarget,
+    pub bytes: u64,
+    pub key_type: String,
+}
+
+/// One level of the memory tree, where clicking a namespace drills down and
+/// clicking a key opens it.
+pub struct MemoryTreemap<Message> {
+    tiles: Vec<Tile>,
+    on_namespace: fn(String) -> Message,
+    on_key: fn(String) -> Message,
+}
+
+impl<Message> MemoryTreemap<Message> {
+    pub fn new(
+        nodes: &[MemoryTreeNode],
+        on_namespace: fn(String) -> Message,
+        on_key: fn(String) -> Message,
+    ) -> Self {
+        let mut tiles: Vec<Tile> = nodes
+            .iter()
+            .filter(|node| node.bytes > 0)
+            .take(MAX_TILES)
+            .map(|node| Tile {
+                label: match &node.key {
+                    Some(key) => key.clone(),
+                    None => format!("{} ({} keys)", node.name, node.keys),
+                },
+                target: match &node.key {
+                    Some(key) => TileTarget::Key(key.clone()),
+                    None => TileTarget::Namespace(node.name.clone()),
+                },
+                bytes: node.bytes,
+                key_type: node.dominant_type().to_string(),
+            })
+            .collect();
+        let rest: Vec<&MemoryTreeNode> = nodes.iter().skip(MAX_TILES).collect();
+        let rest_bytes = rest.iter().map(|node| node.bytes).sum::<u64>();
+        if rest_bytes > 0 {
+            tiles.push(Tile {
+                label: format!("{} more", rest.len()),
+                target: TileTarget::Rest,
+                bytes: rest_bytes,
+                key_type: String::new(),
+            });
+        }
+        MemoryTreemap {
+            tiles,
+            on_namespace,
+            on_key,
+        }
+    }
+}
+
+/// Squarified layout: tiles are laid out in strips along the shorter side,
+/// and a strip grows while that keeps its tiles closer to squares.
+fn squarify(sizes: &[f64], bounds: Size) -> Vec<Rectangle> {
+    let total: f64 = sizes.iter().sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+    let scale = (bounds.width * bounds.height) as f64 / total;
+    let areas: Vec<f64> = sizes.iter().map(|size| size * scale).collect();
+
+    let worst = |strip: &[f64], side: f64| -> f64 {
+        let sum: f64 = strip.iter().sum();
+        strip
+            .iter()
+            .map(|area| (side * side * area / (sum * sum)).max(sum * sum / (side * side * area)))
+            .fold(0.0, f64::max)
+    };
+
+    let (mut x, mut y) = (0.0_f64, 0.0_f64);
+    let (mut width, mut height) = (bounds.width as f64, bounds.height as f64);
+    let mut rectangles = Vec::<Rectangle>::with_capacity(areas.len());
+    let mut start = 0;
+    while start < areas.len() {
+        let side = width.min(height);
+        let mut end = start + 1;
+        while end < areas.len()
+            && worst(&areas[start..=end], side) <= worst(&areas[start..end], side)
+        {
+            end += 1;
+        }
+
+        let strip_area: f64 = areas[start..end].iter().sum();
+        let thickness = if side > 0.0 { strip_area / side } else { 0.0 };
+        let mut offset = 0.0;
+        for area in areas[start..end].iter() {
+            let length = if thickness > 0.0 {
+                area / thickness
+            } else {
+                0.0
+            };
+            let (left, top, w, h) = if width >= height {
+                (x, y + offset, thickness, length)
+            } else {
+                (x + offset, y, length, thickness)
+            };
+            rectangles.push(Rectangle::new(
+                Point::new(left as f32, top as f32),
+                Size::new(w as f32, h as f32),
+            ));
+            offset += length;
+        }
+        if width >= height {
+            x += thickness;
+            width -= thickness;
+        } else {
+            y += thickness;
+            height -= thickness;
+        }
+        start = end;
+    }
+    rectangles
+}
+
+impl<Message> MemoryTreemap<Message> {
+    fn layout(&self, bounds: Size) -> Vec<Rectangle> {
+        let sizes: Vec<f64> = self.tiles.iter().map(|tile| tile.bytes as f64).collect();
+        squarify(&sizes, bounds)
+    }
+}
+
+impl<Message> canvas::Program<Message> for MemoryTreemap<Message> {
+    fn update(
+        &mut self,
+        event: Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<Message>) {
+        let position = match (event, cursor.position_in(&bounds)) {
+            (Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)), Some(position)) => {
+                position
+            }
+            _ => return (event::Status::Ignored, None),
+        };
+        let clicked = self
+            .tiles
+            .iter()
+            .zip(self.layout(bounds.size()))
+            .find(|(_, rectangle)| rectangle.contains(position))
+            .map(|(tile, _)| tile.target.clone());
+        match clicked {
+            Some(TileTarget::Namespace(name)) => {
+                (event::Status::Captured, Some((self.on_namespace)(name)))
+            }
+            Some(TileTarget::Key(key)) => (event::Status::Captured, Some((self.on_key)(key))),
+            Some(TileTarget::Rest) | None => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(&self, bounds: Rectangle, cursor: Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, frame.size(), BACKGROUND);
+
+        let hovered = cursor.position_in(&bounds);
+        for (tile, rectangle) in self.tiles.iter().zip(self.layout(bounds.size())) {
+            let size = Size::new(
+                (rectangle.width - TILE_GAP).max(0.0),
+                (rectangle.height - TILE_GAP).max(0.0),
+            );
+            let color = type_color(&tile.key_type);
+            let color = match hovered {
+                Some(position) if rectangle.contains(position) => Color { a: 0.7, ..color },
+                _ => color,
+            };
+            frame.fill_rectangle(rectangle.position(), size, color);
+
+            let fits = (size.width / LABEL_CHAR_WIDTH) as usize;
+            if size.height < 2.0 * LABEL_SIZE + 4.0 || fits < 4 {
+                continue;
+            }
+            for (line, content) in [tile.label.clone(), format_bytes(tile.bytes)]
+                .iter()
+                .enumerate()
+            {
+                let content = if content.chars().count() > fits {
+                    let cut: String = content.chars().take(fits.saturating_sub(1)).collect();
+                    format!("{}…", cut)
+                } else {
+                    content.clone()
+                };
+                frame.fill_text(Text {
+                    content,
+                    position: Point::new(
+                        rectangle.x + 4.0,
+                        rectangle.y + 2.0 + line as f32 * LABEL_SIZE,
+                    ),
+                    color: Color::WHITE,
+                    size: LABEL_SIZE,
+                    ..Text::default()
+                });
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(&self, bounds: Rectangle, cursor: Cursor) -> mouse::Interaction {
+        if cursor.is_over(&bounds) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}